@@ -3,13 +3,13 @@ use crate::mappings;
 use crate::util::{is_leap_year, parse_i32, year_len};
 use crate::weekday::Weekday;
 use chrono::{Datelike, NaiveDate};
+use core::cmp::Ordering;
+use core::fmt;
 use nom::branch::alt;
 use nom::combinator::{cut, map, map_res};
 use nom::sequence::tuple;
-use std::cmp::Ordering;
-use std::fmt;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ByDay {
     All(Weekday),
     Nth(Weekday, i32),
@@ -73,7 +73,8 @@ impl DaysInMonthIterator {
         match byday {
             ByDay::All(weekday) => {
                 let leap_year = is_leap_year(year);
-                let first_month_day = NaiveDate::from_ymd(year, month1, 1);
+                let first_month_day =
+                    NaiveDate::from_ymd_opt(year, month1, 1).expect("valid month");
                 let first_month_day_yd = first_month_day.ordinal0() as i32;
                 let base_offset = weekday.offset_from(first_month_day.weekday()) as i32;
                 let base_offset = first_month_day_yd + base_offset;
@@ -169,7 +170,7 @@ fn nth_weekday_in_month(year: i32, month1: u32, weekday: Weekday, nth: i32) -> O
             mappings::YEARDAY_TO_MONTH_NORMAL[yd as usize] as u32 == month1
         };
 
-        same_month.then(|| yd)
+        same_month.then_some(yd)
     } else {
         None
     }
@@ -190,7 +191,7 @@ impl DaysInYearIterator {
     fn new(byday: ByDay, year: i32) -> Self {
         match byday {
             ByDay::All(weekday) => {
-                let first_year_day = NaiveDate::from_yo(year, 1);
+                let first_year_day = NaiveDate::from_yo_opt(year, 1).expect("valid year");
                 let year_len = year_len(year) as i32;
                 let base_offset = weekday.offset_from(first_year_day.weekday()) as i32;
 
@@ -236,7 +237,7 @@ fn nth_weekday_in_year(year: i32, weekday: Weekday, nth: i32) -> Option<i32> {
 
     let yd = match nth.cmp(&0) {
         Ordering::Less => {
-            let last_year_day = NaiveDate::from_yo(year, year_len);
+            let last_year_day = NaiveDate::from_yo_opt(year, year_len).expect("valid year");
             let nth = -(nth + 1);
 
             let base_offset = weekday.days_until(last_year_day.weekday()) as i32;
@@ -245,7 +246,7 @@ fn nth_weekday_in_year(year: i32, weekday: Weekday, nth: i32) -> Option<i32> {
             last_year_day.ordinal0() as i32 - offset
         }
         Ordering::Greater => {
-            let first_year_day = NaiveDate::from_yo(year, 1);
+            let first_year_day = NaiveDate::from_yo_opt(year, 1).expect("valid year");
             let nth = nth - 1;
 
             let base_offset = weekday.offset_from(first_year_day.weekday()) as i32;
@@ -0,0 +1,279 @@
+use crate::dt_prop::DtProperty;
+use crate::equivalence::{lcm, DEFAULT_HORIZON};
+use crate::iter::{RRuleIterYield, RRuleSetIter};
+use crate::rrule_set::RRuleSet;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use chrono::{Duration, NaiveDateTime};
+use core::iter::Peekable;
+
+/// A recurring event, whose occurrences start as given by a rule set and last for `duration`
+#[derive(Debug, Clone)]
+pub struct RecurringEvent {
+    pub set: RRuleSet,
+    pub duration: Duration,
+}
+
+/// Two overlapping occurrences, of the event and of the other event
+#[derive(Debug, Clone, Copy)]
+pub struct Conflict {
+    pub this: RRuleIterYield,
+    pub other: RRuleIterYield,
+}
+
+impl RecurringEvent {
+    pub fn new(set: RRuleSet, duration: Duration) -> Self {
+        Self { set, duration }
+    }
+
+    /// First pair of overlapping occurrences of both events
+    ///
+    /// Unbounded events of the same timezone repeat after their common cycle, if they do not
+    /// conflict within the first cycle after all their RDATEs and EXDATEs they never do. DST
+    /// transitions are not considered for this. In all other cases at most
+    /// [`DEFAULT_HORIZON`] occurrences are compared.
+    pub fn first_conflict(&self, other: &RecurringEvent) -> Option<Conflict> {
+        let mut iter = self.conflicts_iter(other);
+
+        match self.common_cycle_end(other) {
+            Some(end) => iter.end_local = Some(end),
+            None => iter.limit = Some(DEFAULT_HORIZON),
+        }
+
+        iter.next()
+    }
+
+    /// All conflicts of occurrences starting before `end`
+    ///
+    /// `end` is UTC for events with a timezone and local time for floating ones.
+    pub fn conflicts(&self, other: &RecurringEvent, end: NaiveDateTime) -> Vec<Conflict> {
+        let mut iter = self.conflicts_iter(other);
+        iter.end = Some(end);
+        iter.collect()
+    }
+
+    /// Iterator over all conflicts, in the order of the later occurrence of each pair
+    ///
+    /// The iterator may not terminate for unbounded events which never conflict.
+    pub fn conflicts_iter(&self, other: &RecurringEvent) -> ConflictIter {
+        ConflictIter {
+            this: Side::new(self),
+            other: Side::new(other),
+            pending: VecDeque::new(),
+            end: None,
+            end_local: None,
+            limit: None,
+        }
+    }
+
+    fn is_bounded(&self) -> bool {
+        self.set
+            .rrules
+            .iter()
+            .all(|recur| recur.count.is_some() || recur.until.is_some())
+    }
+
+    /// Local time after which both unbounded events cannot have a first conflict
+    fn common_cycle_end(&self, other: &RecurringEvent) -> Option<NaiveDateTime> {
+        if self.is_bounded()
+            || other.is_bounded()
+            || self.set.dt_start.0.result_tz() != other.set.dt_start.0.result_tz()
+        {
+            return None;
+        }
+
+        let mut cycle = 1;
+        let mut periodic_from = NaiveDateTime::MIN;
+
+        for event in [self, other] {
+            let set = &event.set;
+
+            for recur in set.rrules.iter().chain(&set.exrules) {
+                cycle = lcm(cycle, recur.cycle()?)?;
+            }
+
+            let local = |dt: DtProperty| match set.dt_start.0.result_tz() {
+                Some(tz) => dt.to_datetime().with_timezone(&tz).naive_local(),
+                None => dt.to_datetime().naive_local(),
+            };
+
+            let dates = set
+                .rdates
+                .iter()
+                .map(|rdate| rdate.0)
+                .chain(set.exdates.iter().map(|exdate| exdate.0))
+                .chain([set.dt_start.0]);
+
+            for dt in dates {
+                periodic_from = periodic_from.max(local(dt));
+            }
+        }
+
+        periodic_from
+            .checked_add_signed(Duration::try_seconds(cycle)?)?
+            .checked_add_signed(self.duration.max(other.duration))
+    }
+}
+
+/// Occurrences of one event, with those which may still overlap with the other's
+#[derive(Debug)]
+struct Side {
+    iter: Peekable<RRuleSetIter>,
+    duration: Duration,
+    active: VecDeque<(NaiveDateTime, RRuleIterYield)>,
+}
+
+impl Side {
+    fn new(event: &RecurringEvent) -> Self {
+        Self {
+            iter: event.set.iter().peekable(),
+            duration: event.duration,
+            active: VecDeque::new(),
+        }
+    }
+
+    /// Drop occurrences which ended before `start`
+    fn expire(&mut self, start: NaiveDateTime) {
+        while self
+            .active
+            .front()
+            .is_some_and(|&(begin, _)| begin + self.duration <= start)
+        {
+            self.active.pop_front();
+        }
+    }
+}
+
+/// Iterator over the [`Conflict`]s of two events, see [`RecurringEvent::conflicts_iter`]
+///
+/// Both occurrence streams are swept in order, so each occurrence is only compared with the
+/// occurrences of the other event it overlaps with.
+#[derive(Debug)]
+pub struct ConflictIter {
+    this: Side,
+    other: Side,
+    pending: VecDeque<Conflict>,
+
+    // Bounds of the sweep by start of the occurrences
+    end: Option<NaiveDateTime>,
+    end_local: Option<NaiveDateTime>,
+    limit: Option<usize>,
+}
+
+impl Iterator for ConflictIter {
+    type Item = Conflict;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(conflict) = self.pending.pop_front() {
+                return Some(conflict);
+            }
+
+            // Occurrences left to compare
+            if let Some(limit) = &mut self.limit {
+                if *limit == 0 {
+                    return None;
+                }
+
+                *limit -= 1;
+            }
+
+            let take_this = match (self.this.iter.peek(), self.other.iter.peek()) {
+                (None, None) => return None,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (Some(this), Some(other)) => this.sort_key() <= other.sort_key(),
+            };
+
+            let (own, opposite) = if take_this {
+                (&mut self.this, &mut self.other)
+            } else {
+                (&mut self.other, &mut self.this)
+            };
+
+            let occurrence = own.iter.next().expect("peeked");
+            let start = occurrence.sort_key();
+
+            if self.end.is_some_and(|end| start >= end)
+                || self.end_local.is_some_and(|end| occurrence.local() > end)
+            {
+                return None;
+            }
+
+            opposite.expire(start);
+
+            for &(_, overlapping) in &opposite.active {
+                self.pending.push_back(if take_this {
+                    Conflict {
+                        this: occurrence,
+                        other: overlapping,
+                    }
+                } else {
+                    Conflict {
+                        this: overlapping,
+                        other: occurrence,
+                    }
+                });
+            }
+
+            own.active.push_back((start, occurrence));
+
+            // Once one event has ended, the other's later occurrences cannot conflict
+            if opposite.iter.peek().is_none() && opposite.active.is_empty() {
+                self.limit = Some(0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn event(s: &str, minutes: i64) -> RecurringEvent {
+        RecurringEvent::new(s.parse().unwrap(), Duration::minutes(minutes))
+    }
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn first_conflict() {
+        let weekly = event(
+            "DTSTART;TZID=Europe/Berlin:20220103T090000\nRRULE:FREQ=WEEKLY;BYDAY=TU",
+            60,
+        );
+        let monthly = event(
+            "DTSTART;TZID=Europe/Berlin:20220103T093000\nRRULE:FREQ=MONTHLY;BYMONTHDAY=15",
+            30,
+        );
+
+        let conflict = weekly.first_conflict(&monthly).unwrap();
+
+        assert_eq!(conflict.this.local().to_string(), "2022-02-15 09:00:00");
+        assert_eq!(conflict.other.local().to_string(), "2022-02-15 09:30:00");
+
+        // Back to back occurrences do not conflict
+        let later = event(
+            "DTSTART;TZID=Europe/Berlin:20220103T100000\nRRULE:FREQ=DAILY",
+            60,
+        );
+
+        assert!(weekly.first_conflict(&later).is_none());
+    }
+
+    #[test]
+    fn conflicts() {
+        let a = event("DTSTART:20220103T090000\nRRULE:FREQ=DAILY;COUNT=5", 90);
+        let b = event("DTSTART:20220104T100000\nRRULE:FREQ=WEEKLY;BYDAY=TU,TH", 30);
+
+        let end = NaiveDateTime::parse_from_str("2022-02-01T00:00:00", "%Y-%m-%dT%H:%M:%S");
+        let conflicts = a.conflicts(&b, end.unwrap());
+
+        let starts: Vec<_> = conflicts
+            .iter()
+            .map(|conflict| conflict.other.local().to_string())
+            .collect();
+
+        assert_eq!(starts, ["2022-01-04 10:00:00", "2022-01-06 10:00:00"]);
+    }
+}
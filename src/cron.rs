@@ -0,0 +1,749 @@
+use crate::byday::ByDay;
+use crate::error::{IResult, ParseError};
+use crate::freq::Frequency;
+use crate::recur::Recur;
+use crate::rrule::RRule;
+use crate::util::parse_u32;
+use crate::weekday::Weekday;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use chrono::{Datelike, Timelike};
+use nom::branch::alt;
+use nom::bytes::complete::{tag, tag_no_case};
+use nom::character::complete::char;
+use nom::combinator::{all_consuming, map, opt, value};
+use nom::multi::separated_list1;
+use nom::sequence::{pair, preceded, separated_pair, terminated};
+use nom::Finish;
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+const WEEKDAYS: [Weekday; 5] = [
+    Weekday::Monday,
+    Weekday::Tuesday,
+    Weekday::Wednesday,
+    Weekday::Thursday,
+    Weekday::Friday,
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum CronParseError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("expected 5 or 6 fields, got {0}")]
+    FieldCount(usize),
+    #[error("value {1} out of range for {0}")]
+    OutOfRange(&'static str, u32),
+    #[error("step of {0} must not be 0")]
+    ZeroStep(&'static str),
+    #[error("range {1}-{2} of {0} is reversed")]
+    ReversedRange(&'static str, u32, u32),
+    #[error("{0} cannot be expressed as RRULE")]
+    Unsupported(&'static str),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CronFormatError {
+    #[error("{0} cannot be expressed in cron")]
+    Unsupported(&'static str),
+}
+
+struct FieldSpec {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+    names_offset: u32,
+}
+
+const SECOND: FieldSpec = FieldSpec {
+    name: "second",
+    min: 0,
+    max: 59,
+    names: &[],
+    names_offset: 0,
+};
+
+const MINUTE: FieldSpec = FieldSpec {
+    name: "minute",
+    min: 0,
+    max: 59,
+    names: &[],
+    names_offset: 0,
+};
+
+const HOUR: FieldSpec = FieldSpec {
+    name: "hour",
+    min: 0,
+    max: 23,
+    names: &[],
+    names_offset: 0,
+};
+
+const DAY_OF_MONTH: FieldSpec = FieldSpec {
+    name: "day of month",
+    min: 1,
+    max: 31,
+    names: &[],
+    names_offset: 0,
+};
+
+const MONTH: FieldSpec = FieldSpec {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &MONTH_NAMES,
+    names_offset: 1,
+};
+
+// 0 and 7 are both sunday
+const DAY_OF_WEEK: FieldSpec = FieldSpec {
+    name: "day of week",
+    min: 0,
+    max: 7,
+    names: &WEEKDAY_NAMES,
+    names_offset: 0,
+};
+
+#[derive(Debug, Clone, Copy)]
+enum Item {
+    /// `*` or `?` with optional step
+    All(u32),
+    /// `a`, `a-b`, `a/n` or `a-b/n`
+    Range(u32, Option<u32>, u32),
+    /// `L`
+    Last,
+    /// `LW`
+    LastWeekday,
+    /// `nW`
+    NearestWeekday(u32),
+    /// `dL`
+    LastOf(u32),
+    /// `d#n`
+    NthOf(u32, u32),
+}
+
+fn parse_value(spec: &'static FieldSpec) -> impl Fn(&str) -> IResult<&str, u32> {
+    move |i| {
+        for (idx, name) in spec.names.iter().enumerate() {
+            if let Ok((rem, _)) = tag_no_case::<_, _, ParseError>(*name)(i) {
+                return Ok((rem, idx as u32 + spec.names_offset));
+            }
+        }
+
+        parse_u32(i)
+    }
+}
+
+fn parse_item(spec: &'static FieldSpec) -> impl Fn(&str) -> IResult<&str, Item> {
+    move |i| {
+        let step = || opt(preceded(char('/'), parse_u32));
+
+        alt((
+            value(Item::LastWeekday, tag_no_case("LW")),
+            value(Item::Last, tag_no_case("L")),
+            map(
+                terminated(parse_value(spec), tag_no_case("W")),
+                Item::NearestWeekday,
+            ),
+            map(
+                terminated(parse_value(spec), tag_no_case("L")),
+                Item::LastOf,
+            ),
+            map(
+                separated_pair(parse_value(spec), char('#'), parse_u32),
+                |(d, n)| Item::NthOf(d, n),
+            ),
+            map(preceded(alt((tag("*"), tag("?"))), step()), |step| {
+                Item::All(step.unwrap_or(1))
+            }),
+            map(
+                pair(
+                    pair(
+                        parse_value(spec),
+                        opt(preceded(char('-'), parse_value(spec))),
+                    ),
+                    step(),
+                ),
+                |((start, end), step)| Item::Range(start, end, step.unwrap_or(1)),
+            ),
+        ))(i)
+    }
+}
+
+/// A parsed cron field, `None` if it matches everything
+struct Field {
+    values: Option<Vec<u32>>,
+    specials: Vec<Item>,
+}
+
+fn parse_field(spec: &'static FieldSpec, i: &str) -> Result<Field, CronParseError> {
+    let (_, items) = all_consuming(separated_list1(char(','), parse_item(spec)))(i).finish()?;
+
+    let check = |v: u32| {
+        if (spec.min..=spec.max).contains(&v) {
+            Ok(v)
+        } else {
+            Err(CronParseError::OutOfRange(spec.name, v))
+        }
+    };
+
+    let mut values = vec![];
+    let mut specials = vec![];
+
+    for item in items {
+        match item {
+            Item::All(0) | Item::Range(_, _, 0) => return Err(CronParseError::ZeroStep(spec.name)),
+            Item::All(1) => {
+                return Ok(Field {
+                    values: None,
+                    specials: vec![],
+                })
+            }
+            Item::All(step) => {
+                values.extend((spec.min..=spec.max).step_by(step as usize));
+            }
+            Item::Range(start, end, step) => {
+                let start = check(start)?;
+                // `a/n` is `a-max/n`
+                let end = match end {
+                    Some(end) => check(end)?,
+                    None if step > 1 => spec.max,
+                    None => start,
+                };
+
+                if end < start {
+                    return Err(CronParseError::ReversedRange(spec.name, start, end));
+                }
+
+                values.extend((start..=end).step_by(step as usize));
+            }
+            Item::NearestWeekday(day) | Item::LastOf(day) | Item::NthOf(day, _) => {
+                check(day)?;
+                specials.push(item);
+            }
+            Item::Last | Item::LastWeekday => specials.push(item),
+        }
+    }
+
+    values.sort_unstable();
+    values.dedup();
+
+    Ok(Field {
+        values: Some(values),
+        specials,
+    })
+}
+
+fn cron_weekday(d: u32) -> Weekday {
+    match d % 7 {
+        0 => Weekday::Sunday,
+        1 => Weekday::Monday,
+        2 => Weekday::Tuesday,
+        3 => Weekday::Wednesday,
+        4 => Weekday::Thursday,
+        5 => Weekday::Friday,
+        _ => Weekday::Saturday,
+    }
+}
+
+impl Recur {
+    /// Convert a 5 field (`minute hour day-of-month month day-of-week`) or
+    /// 6 field (leading seconds) cron expression
+    ///
+    /// Day of week numbers follow unix cron (0 and 7 are sunday).
+    /// The quartz extensions `L`, `LW`, `1W`, `dL` and `d#n` are supported.
+    pub fn from_cron(expr: &str) -> Result<Self, CronParseError> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+
+        let (second, rest) = match fields.len() {
+            5 => (None, &fields[..]),
+            6 => (Some(parse_field(&SECOND, fields[0])?), &fields[1..]),
+            n => return Err(CronParseError::FieldCount(n)),
+        };
+
+        let minute = parse_field(&MINUTE, rest[0])?;
+        let hour = parse_field(&HOUR, rest[1])?;
+        let day_of_month = parse_field(&DAY_OF_MONTH, rest[2])?;
+        let month = parse_field(&MONTH, rest[3])?;
+        let day_of_week = parse_field(&DAY_OF_WEEK, rest[4])?;
+
+        let mut recur = Recur::new(Frequency::Daily);
+
+        // Day of month
+        let mut set_pos = None;
+
+        if let Some(days) = &day_of_month.values {
+            recur.by_month_day.extend(days.iter().map(|&d| d as i32));
+        }
+
+        for special in &day_of_month.specials {
+            match special {
+                Item::Last => recur.by_month_day.push(-1),
+                Item::LastWeekday => set_pos = Some(-1),
+                Item::NearestWeekday(1) => set_pos = Some(1),
+                Item::NearestWeekday(_) => return Err(CronParseError::Unsupported("nW")),
+                _ => {
+                    return Err(CronParseError::Unsupported(
+                        "day of week syntax in day of month",
+                    ))
+                }
+            }
+        }
+
+        if set_pos.is_some() && (!recur.by_month_day.is_empty() || day_of_month.specials.len() > 1)
+        {
+            return Err(CronParseError::Unsupported("W combined with other days"));
+        }
+
+        // Day of week
+        if let Some(days) = &day_of_week.values {
+            recur
+                .by_day
+                .extend(days.iter().map(|&d| ByDay::All(cron_weekday(d))));
+        }
+
+        for special in &day_of_week.specials {
+            match *special {
+                Item::LastOf(d) => recur.by_day.push(ByDay::Nth(cron_weekday(d), -1)),
+                Item::NthOf(d, n @ 1..=5) => {
+                    recur.by_day.push(ByDay::Nth(cron_weekday(d), n as i32))
+                }
+                Item::NthOf(_, n) => return Err(CronParseError::OutOfRange("#", n)),
+                _ => {
+                    return Err(CronParseError::Unsupported(
+                        "day of month syntax in day of week",
+                    ))
+                }
+            }
+        }
+
+        // cron matches either day of month or day of week if both are restricted
+        if (!recur.by_month_day.is_empty() || set_pos.is_some()) && !recur.by_day.is_empty() {
+            return Err(CronParseError::Unsupported(
+                "restricting both day of month and day of week",
+            ));
+        }
+
+        if let Some(set_pos) = set_pos {
+            recur.by_day = WEEKDAYS.iter().copied().map(ByDay::All).collect();
+            recur.by_set_pos.push(set_pos);
+        }
+
+        if let Some(months) = month.values {
+            recur.by_month = months;
+        }
+
+        let needs_month = set_pos.is_some() || !day_of_week.specials.is_empty();
+
+        if needs_month {
+            // BYSETPOS counts every occurrence inside the month
+            let single = |f: &Option<Vec<u32>>| f.as_ref().is_some_and(|v| v.len() == 1);
+
+            if set_pos.is_some()
+                && !(single(&minute.values)
+                    && single(&hour.values)
+                    && second.as_ref().is_none_or(|s| single(&s.values)))
+            {
+                return Err(CronParseError::Unsupported("W with multiple times per day"));
+            }
+
+            recur.freq = Frequency::Monthly;
+            recur.by_hour = hour.values.unwrap_or_else(|| (0..24).collect());
+            recur.by_minute = minute.values.unwrap_or_else(|| (0..60).collect());
+
+            if let Some(second) = second {
+                recur.by_second = second.values.unwrap_or_else(|| (0..60).collect());
+            }
+
+            recur.sort_and_dedup();
+            return Ok(recur);
+        }
+
+        // Pick the frequency from the finest field matching everything
+        let second = second.map(|s| s.values);
+
+        recur.freq = if matches!(second, Some(None)) {
+            Frequency::Secondly
+        } else if minute.values.is_none() {
+            Frequency::Minutely
+        } else if hour.values.is_none() {
+            Frequency::Hourly
+        } else {
+            Frequency::Daily
+        };
+
+        recur.by_second = second.flatten().unwrap_or_default();
+        recur.by_minute = minute.values.unwrap_or_default();
+        recur.by_hour = hour.values.unwrap_or_default();
+
+        recur.sort_and_dedup();
+
+        Ok(recur)
+    }
+}
+
+impl RRule {
+    /// Best-effort conversion to a cron expression
+    ///
+    /// Parts not set by the rule are taken from DTSTART, the timezone is ignored.
+    /// A 6 field expression is returned if seconds other than 0 are required.
+    pub fn to_cron(&self) -> Result<String, CronFormatError> {
+        let mut recur = self.recur.clone();
+        recur.sort_and_dedup();
+
+        let dt_start = self.dt_start.0.to_datetime().naive_local();
+
+        if recur.count.is_some() {
+            return Err(CronFormatError::Unsupported("COUNT"));
+        }
+
+        if recur.until.is_some() {
+            return Err(CronFormatError::Unsupported("UNTIL"));
+        }
+
+        if !recur.by_year_day.is_empty() {
+            return Err(CronFormatError::Unsupported("BYYEARDAY"));
+        }
+
+        if !recur.by_week_no.is_empty() {
+            return Err(CronFormatError::Unsupported("BYWEEKNO"));
+        }
+
+        let interval = recur.interval.unwrap_or(1);
+
+        let (minute_step, hour_step) = match (recur.freq, interval) {
+            (_, 1) => (1, 1),
+            (Frequency::Minutely, n) if 60 % n == 0 && recur.by_minute.is_empty() => (n, 1),
+            (Frequency::Hourly, n) if 24 % n == 0 && recur.by_hour.is_empty() => (1, n),
+            _ => return Err(CronFormatError::Unsupported("INTERVAL")),
+        };
+
+        let time_field = |values: &[u32], every: bool, step: u32, default: u32| {
+            if !values.is_empty() {
+                format_list(values.to_vec())
+            } else if every && step > 1 {
+                // The step divides the hour or day, so its phase is that of DTSTART
+                match default % step {
+                    0 => format!("*/{}", step),
+                    phase => format!("{}/{}", phase, step),
+                }
+            } else if every {
+                "*".into()
+            } else {
+                default.to_string()
+            }
+        };
+
+        let second = time_field(
+            &recur.by_second,
+            recur.freq == Frequency::Secondly,
+            1,
+            dt_start.second(),
+        );
+        let minute = time_field(
+            &recur.by_minute,
+            matches!(recur.freq, Frequency::Secondly | Frequency::Minutely),
+            minute_step,
+            dt_start.minute(),
+        );
+        let hour = time_field(
+            &recur.by_hour,
+            recur.freq.is_sub_daily(),
+            hour_step,
+            dt_start.hour(),
+        );
+
+        let weekdays: Vec<Weekday> = recur
+            .by_day
+            .iter()
+            .filter_map(|by_day| match by_day {
+                ByDay::All(weekday) => Some(*weekday),
+                ByDay::Nth(..) => None,
+            })
+            .collect();
+
+        let nth: Vec<(Weekday, i32)> = recur
+            .by_day
+            .iter()
+            .filter_map(|by_day| match by_day {
+                ByDay::Nth(weekday, nth) => Some((*weekday, *nth)),
+                ByDay::All(_) => None,
+            })
+            .collect();
+
+        let mut day_of_month = "*".to_string();
+        let mut day_of_week = "*".to_string();
+
+        if !recur.by_set_pos.is_empty() {
+            // W selects a day of each month, BYSETPOS counts every occurrence inside the month
+            let is_month_scope = recur.freq == Frequency::Monthly;
+            let single_time = recur.by_hour.len() <= 1
+                && recur.by_minute.len() <= 1
+                && recur.by_second.len() <= 1;
+
+            if is_month_scope && !single_time {
+                return Err(CronFormatError::Unsupported(
+                    "BYSETPOS with multiple times per day",
+                ));
+            }
+
+            day_of_month = match recur.by_set_pos.as_slice() {
+                [1] if is_month_scope && weekdays == WEEKDAYS => "1W".into(),
+                [-1] if is_month_scope && weekdays == WEEKDAYS => "LW".into(),
+                _ => return Err(CronFormatError::Unsupported("BYSETPOS")),
+            };
+
+            if !recur.by_month_day.is_empty() || recur.by_day.len() != weekdays.len() {
+                return Err(CronFormatError::Unsupported("BYSETPOS"));
+            }
+        } else {
+            if !recur.by_day.is_empty() && !recur.by_month_day.is_empty() {
+                return Err(CronFormatError::Unsupported("BYDAY with BYMONTHDAY"));
+            }
+
+            match (nth.as_slice(), weekdays.is_empty()) {
+                ([], false) => {
+                    day_of_week = format_list(weekdays.iter().map(|&w| unix_weekday(w)).collect());
+                }
+                ([(weekday, -1)], true) => day_of_week = format!("{}L", unix_weekday(*weekday)),
+                ([(weekday, n @ 1..=5)], true) => {
+                    day_of_week = format!("{}#{}", unix_weekday(*weekday), n)
+                }
+                ([], true) => {}
+                _ => return Err(CronFormatError::Unsupported("BYDAY")),
+            }
+
+            let mut days = vec![];
+            let mut last = false;
+
+            for &day in &recur.by_month_day {
+                match day {
+                    1..=31 => days.push(day as u32),
+                    -1 => last = true,
+                    _ => return Err(CronFormatError::Unsupported("negative BYMONTHDAY")),
+                }
+            }
+
+            if !days.is_empty() || last {
+                let mut list = if days.is_empty() {
+                    String::new()
+                } else {
+                    format_list(days)
+                };
+
+                if last {
+                    if !list.is_empty() {
+                        list.push(',');
+                    }
+
+                    list.push('L');
+                }
+
+                day_of_month = list;
+            }
+
+            let has_days = !recur.by_day.is_empty() || !recur.by_month_day.is_empty();
+
+            match recur.freq {
+                Frequency::Weekly if !has_days => {
+                    day_of_week = unix_weekday_chrono(dt_start.weekday()).to_string();
+                }
+                Frequency::Monthly | Frequency::Yearly if !has_days => {
+                    day_of_month = dt_start.day().to_string();
+                }
+                _ => {}
+            }
+        }
+
+        let month = if !recur.by_month.is_empty() {
+            format_list(recur.by_month.clone())
+        } else if recur.freq == Frequency::Yearly {
+            match nth.as_slice() {
+                // Without BYMONTH the nth weekday is that of the year
+                // The 5th weekday of a year may be in February
+                [(_, 1..=4)] => "1".into(),
+                [(_, -1)] => "12".into(),
+                [] if recur.by_day.is_empty() && recur.by_month_day.is_empty() => {
+                    dt_start.month().to_string()
+                }
+                // BYDAY and BYMONTHDAY expand the year into every month
+                [] => "*".into(),
+                _ => return Err(CronFormatError::Unsupported("BYDAY")),
+            }
+        } else {
+            "*".into()
+        };
+
+        if second == "0" {
+            Ok(format!(
+                "{} {} {} {} {}",
+                minute, hour, day_of_month, month, day_of_week
+            ))
+        } else {
+            Ok(format!(
+                "{} {} {} {} {} {}",
+                second, minute, hour, day_of_month, month, day_of_week
+            ))
+        }
+    }
+}
+
+fn unix_weekday(weekday: Weekday) -> u32 {
+    (weekday as u32 + 1) % 7
+}
+
+fn unix_weekday_chrono(weekday: chrono::Weekday) -> u32 {
+    weekday.num_days_from_sunday()
+}
+
+/// Format values as cron list, compressing runs into ranges: 1,2,3,5 => 1-3,5
+fn format_list(mut values: Vec<u32>) -> String {
+    values.sort_unstable();
+    values.dedup();
+
+    let mut out = vec![];
+    let mut iter = values.into_iter().peekable();
+
+    while let Some(start) = iter.next() {
+        let mut end = start;
+
+        while iter.peek() == Some(&(end + 1)) {
+            end = iter.next().unwrap();
+        }
+
+        match end - start {
+            0 => out.push(start.to_string()),
+            1 => {
+                out.push(start.to_string());
+                out.push(end.to_string());
+            }
+            _ => out.push(format!("{}-{}", start, end)),
+        }
+    }
+
+    out.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_cron(expr: &str) -> String {
+        Recur::from_cron(expr).unwrap().to_string()
+    }
+
+    #[test]
+    fn from_cron_expr() {
+        assert_eq!(
+            from_cron("0 9 * * 1-5"),
+            "FREQ=DAILY;BYMINUTE=0;BYHOUR=9;BYDAY=MO,TU,WE,TH,FR"
+        );
+        assert_eq!(from_cron("*/15 * * * *"), "FREQ=HOURLY;BYMINUTE=0,15,30,45");
+        assert_eq!(
+            from_cron("30 0 12 L * ?"),
+            "FREQ=DAILY;BYSECOND=30;BYMINUTE=0;BYHOUR=12;BYMONTHDAY=-1"
+        );
+        assert_eq!(
+            from_cron("0 10 ? JAN,JUL FRI#3"),
+            "FREQ=MONTHLY;BYMINUTE=0;BYHOUR=10;BYDAY=3FR;BYMONTH=1,7"
+        );
+        assert_eq!(
+            from_cron("0 18 LW * *"),
+            "FREQ=MONTHLY;BYMINUTE=0;BYHOUR=18;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1"
+        );
+
+        assert!(matches!(
+            Recur::from_cron("0 9 1 * 1"),
+            Err(CronParseError::Unsupported(_))
+        ));
+        assert!(matches!(
+            Recur::from_cron("0 9 * *"),
+            Err(CronParseError::FieldCount(4))
+        ));
+        assert!(matches!(
+            Recur::from_cron("0 25 * * *"),
+            Err(CronParseError::OutOfRange("hour", 25))
+        ));
+        assert!(matches!(
+            Recur::from_cron("*/0 * * * *"),
+            Err(CronParseError::ZeroStep("minute"))
+        ));
+        assert!(matches!(
+            Recur::from_cron("0 9 * * 5-1"),
+            Err(CronParseError::ReversedRange("day of week", 5, 1))
+        ));
+    }
+
+    #[test]
+    fn to_cron() {
+        let to_cron = |s: &str| s.parse::<RRule>().unwrap().to_cron();
+
+        assert_eq!(
+            to_cron("DTSTART:20200106T090000\nRRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR").unwrap(),
+            "0 9 * * 1-5"
+        );
+        assert_eq!(
+            to_cron("DTSTART:20200106T090000\nRRULE:FREQ=MONTHLY;BYDAY=-1FR").unwrap(),
+            "0 9 * * 5L"
+        );
+        assert_eq!(
+            to_cron("DTSTART:20200106T090000\nRRULE:FREQ=MINUTELY;INTERVAL=15").unwrap(),
+            "*/15 * * * *"
+        );
+        assert_eq!(
+            to_cron("DTSTART:20200106T090000\nRRULE:FREQ=HOURLY;INTERVAL=2").unwrap(),
+            "0 1/2 * * *"
+        );
+        assert_eq!(
+            to_cron("DTSTART:20200106T090030\nRRULE:FREQ=YEARLY").unwrap(),
+            "30 0 9 6 1 *"
+        );
+        assert_eq!(
+            to_cron("DTSTART:20200106T090000\nRRULE:FREQ=YEARLY;BYMONTHDAY=1").unwrap(),
+            "0 9 1 * *"
+        );
+        assert_eq!(
+            to_cron("DTSTART:20200106T090000\nRRULE:FREQ=YEARLY;BYDAY=-1FR").unwrap(),
+            "0 9 * 12 5L"
+        );
+        assert_eq!(
+            to_cron("DTSTART:20200106T090000\nRRULE:FREQ=YEARLY;BYDAY=2MO").unwrap(),
+            "0 9 * 1 1#2"
+        );
+        assert_eq!(
+            to_cron("DTSTART:20200106T090000\nRRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1")
+                .unwrap(),
+            "0 9 LW * *"
+        );
+        // W is the first weekday of every month, not of the year
+        assert!(matches!(
+            to_cron("DTSTART:20200106T090000\nRRULE:FREQ=YEARLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=1"),
+            Err(CronFormatError::Unsupported("BYSETPOS"))
+        ));
+        // BYSETPOS selects the last of all times of the month, LW fires at each of them
+        assert!(matches!(
+            to_cron(
+                "DTSTART:20200106T090000\n\
+                RRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=9,17;BYSETPOS=-1"
+            ),
+            Err(CronFormatError::Unsupported(
+                "BYSETPOS with multiple times per day"
+            ))
+        ));
+        // 2021-02-01 is the 5th Monday of 2021
+        assert!(matches!(
+            to_cron("DTSTART:20200106T090000\nRRULE:FREQ=YEARLY;BYDAY=5MO"),
+            Err(CronFormatError::Unsupported("BYDAY"))
+        ));
+        assert!(matches!(
+            to_cron("DTSTART:20200106T090000\nRRULE:FREQ=DAILY;COUNT=3"),
+            Err(CronFormatError::Unsupported("COUNT"))
+        ));
+    }
+}
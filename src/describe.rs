@@ -0,0 +1,343 @@
+use crate::byday::ByDay;
+use crate::dt::Dt;
+use crate::freq::Frequency;
+use crate::locale::{DaySet, English, Locale};
+use crate::recur::Recur;
+use crate::rrule::RRule;
+use crate::weekday::Weekday;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use chrono::{NaiveTime, Timelike};
+use core::fmt;
+
+const WEEKDAYS: [Weekday; 5] = [
+    Weekday::Monday,
+    Weekday::Tuesday,
+    Weekday::Wednesday,
+    Weekday::Thursday,
+    Weekday::Friday,
+];
+
+const WEEKEND: [Weekday; 2] = [Weekday::Saturday, Weekday::Sunday];
+
+const ALL_DAYS: [Weekday; 7] = [
+    Weekday::Monday,
+    Weekday::Tuesday,
+    Weekday::Wednesday,
+    Weekday::Thursday,
+    Weekday::Friday,
+    Weekday::Saturday,
+    Weekday::Sunday,
+];
+
+/// Human readable description of a [`RRule`], see [`RRule::describe`] and [`RRule::describe_with`]
+pub struct Description<'r> {
+    rrule: &'r RRule,
+    locale: &'r dyn Locale,
+}
+
+impl RRule {
+    pub fn describe(&self) -> Description<'_> {
+        self.describe_with(&English)
+    }
+
+    pub fn describe_with<'r>(&'r self, locale: &'r dyn Locale) -> Description<'r> {
+        Description {
+            rrule: self,
+            locale,
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        self.describe().to_string()
+    }
+
+    pub fn to_text_with(&self, locale: &dyn Locale) -> String {
+        self.describe_with(locale).to_string()
+    }
+}
+
+impl fmt::Display for Description<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let locale = self.locale;
+
+        let mut recur = self.rrule.recur.clone();
+        recur.sort_and_dedup();
+
+        let dt_start = self.rrule.dt_start.0.to_datetime().naive_local();
+        let has_time = !self.rrule.dt_start.0.dt.is_date();
+
+        f.write_str(&locale.frequency(recur.freq, recur.interval.unwrap_or(1)))?;
+
+        if recur.freq.is_sub_daily() {
+            write_sub_daily_time(f, locale, &recur, dt_start.time(), has_time)?;
+        }
+
+        write_days(f, locale, &recur)?;
+
+        if !recur.by_week_no.is_empty() {
+            write!(f, " {}", locale.in_weeks(&recur.by_week_no))?;
+        }
+
+        if !recur.by_month.is_empty() {
+            write!(f, " {}", locale.in_months(&recur.by_month))?;
+        }
+
+        if !recur.freq.is_sub_daily() {
+            write_time(f, locale, &recur, dt_start.time(), has_time)?;
+        }
+
+        if let Some(count) = recur.count {
+            write!(f, ", {}", locale.count(count))?;
+        }
+
+        if let Some(until) = recur.until {
+            let until = match until {
+                Dt::Date(date) => date.and_time(NaiveTime::MIN),
+                Dt::DateTimeLocal(datetime) => datetime,
+                Dt::DateTimeUtc(datetime) => match self.rrule.dt_start.0.tz {
+                    Some(tz) => datetime.with_timezone(&tz).naive_local(),
+                    None => datetime.naive_utc(),
+                },
+            };
+
+            write!(f, " {}", locale.until(until, recur.freq.is_sub_daily()))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_days(f: &mut fmt::Formatter<'_>, locale: &dyn Locale, recur: &Recur) -> fmt::Result {
+    let weekdays: Option<Vec<Weekday>> = recur
+        .by_day
+        .iter()
+        .map(|by_day| match by_day {
+            ByDay::All(weekday) => Some(*weekday),
+            ByDay::Nth(..) => None,
+        })
+        .collect();
+
+    let day_set = weekdays.as_deref().and_then(|weekdays| match weekdays {
+        [weekday] => Some(DaySet::Single(*weekday)),
+        w if w == WEEKDAYS => Some(DaySet::Weekdays),
+        w if w == WEEKEND => Some(DaySet::Weekend),
+        w if w == ALL_DAYS => Some(DaySet::All),
+        _ => None,
+    });
+
+    let only_by_day = recur.by_month_day.is_empty()
+        && recur.by_year_day.is_empty()
+        && recur.by_week_no.is_empty();
+
+    // BYSETPOS picking from a plain set of weekdays reads as "the last weekday"
+    if let Some(day_set) = day_set {
+        if !recur.by_set_pos.is_empty() && only_by_day {
+            return write!(f, " {}", locale.nth_of_day_set(&recur.by_set_pos, day_set));
+        }
+    }
+
+    match day_set {
+        Some(day_set @ (DaySet::Weekdays | DaySet::Weekend | DaySet::All)) => {
+            write!(f, " {}", locale.on_day_set(day_set))?;
+        }
+        _ if !recur.by_day.is_empty() => {
+            let of_year =
+                weekdays.is_none() && recur.freq == Frequency::Yearly && recur.by_month.is_empty();
+
+            write!(f, " {}", locale.on_days(&recur.by_day, of_year))?;
+        }
+        _ => {}
+    }
+
+    if !recur.by_month_day.is_empty() {
+        if recur.by_day.is_empty() {
+            let of_month = recur.freq != Frequency::Monthly;
+
+            write!(
+                f,
+                " {}",
+                locale.on_month_days(&recur.by_month_day, of_month)
+            )?;
+        } else {
+            write!(f, " {}", locale.weekday_month_days(&recur.by_month_day))?;
+        }
+    }
+
+    if !recur.by_year_day.is_empty() {
+        write!(f, " {}", locale.on_year_days(&recur.by_year_day))?;
+    }
+
+    if !recur.by_set_pos.is_empty() {
+        write!(f, ", {}", locale.set_pos(&recur.by_set_pos, recur.freq))?;
+    }
+
+    Ok(())
+}
+
+fn write_time(
+    f: &mut fmt::Formatter<'_>,
+    locale: &dyn Locale,
+    recur: &Recur,
+    dt_start: NaiveTime,
+    has_time: bool,
+) -> fmt::Result {
+    if !has_time
+        && recur.by_hour.is_empty()
+        && recur.by_minute.is_empty()
+        && recur.by_second.is_empty()
+    {
+        return Ok(());
+    }
+
+    let hours = or_default(&recur.by_hour, dt_start.hour());
+    let minutes = or_default(&recur.by_minute, dt_start.minute());
+    let seconds = or_default(&recur.by_second, dt_start.second());
+
+    let mut times = vec![];
+
+    for &hour in &hours {
+        for &minute in &minutes {
+            for &second in &seconds {
+                times.extend(NaiveTime::from_hms_opt(hour, minute, second));
+            }
+        }
+    }
+
+    write!(f, " {}", locale.at_times(&times))
+}
+
+fn write_sub_daily_time(
+    f: &mut fmt::Formatter<'_>,
+    locale: &dyn Locale,
+    recur: &Recur,
+    dt_start: NaiveTime,
+    has_time: bool,
+) -> fmt::Result {
+    if !recur.by_hour.is_empty() {
+        write!(f, " {}", locale.during(Frequency::Hourly, &recur.by_hour))?;
+    }
+
+    if recur.freq == Frequency::Hourly {
+        if !recur.by_minute.is_empty() {
+            write!(f, " {}", locale.at(Frequency::Minutely, &recur.by_minute))?;
+        } else if has_time {
+            write!(
+                f,
+                " {}",
+                locale.at(Frequency::Minutely, &[dt_start.minute()])
+            )?;
+        }
+    } else if !recur.by_minute.is_empty() {
+        write!(
+            f,
+            " {}",
+            locale.during(Frequency::Minutely, &recur.by_minute)
+        )?;
+    }
+
+    if recur.freq != Frequency::Secondly {
+        if !recur.by_second.is_empty() {
+            write!(f, " {}", locale.at(Frequency::Secondly, &recur.by_second))?;
+        } else if has_time && dt_start.second() != 0 {
+            write!(
+                f,
+                " {}",
+                locale.at(Frequency::Secondly, &[dt_start.second()])
+            )?;
+        }
+    } else if !recur.by_second.is_empty() {
+        write!(
+            f,
+            " {}",
+            locale.during(Frequency::Secondly, &recur.by_second)
+        )?;
+    }
+
+    Ok(())
+}
+
+fn or_default(values: &[u32], default: u32) -> Vec<u32> {
+    if values.is_empty() {
+        vec![default]
+    } else {
+        values.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::locale::German;
+    use crate::rrule::RRule;
+    use alloc::string::String;
+
+    fn text(s: &str) -> String {
+        s.parse::<RRule>().unwrap().to_text()
+    }
+
+    #[test]
+    fn frequencies() {
+        assert_eq!(
+            text("DTSTART:20200101T090000\nRRULE:FREQ=DAILY"),
+            "Daily at 09:00"
+        );
+        assert_eq!(
+            text("DTSTART:20200101T090000\nRRULE:FREQ=HOURLY;INTERVAL=3"),
+            "Every 3 hours at minute 0"
+        );
+        assert_eq!(
+            text("DTSTART;VALUE=DATE:20200101\nRRULE:FREQ=YEARLY;BYMONTH=1,7"),
+            "Yearly in January and July"
+        );
+    }
+
+    #[test]
+    fn by_day() {
+        assert_eq!(
+            text("DTSTART:20200106T100000\nRRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=WE,MO;COUNT=10"),
+            "Every 2 weeks on Monday and Wednesday at 10:00, 10 times"
+        );
+        assert_eq!(
+            text("DTSTART;VALUE=DATE:20200131\nRRULE:FREQ=MONTHLY;BYDAY=-1FR;UNTIL=20251231"),
+            "Monthly on the last Friday until Dec 31, 2025"
+        );
+        assert_eq!(
+            text("DTSTART;VALUE=DATE:20200131\nRRULE:FREQ=YEARLY;BYDAY=-2MO,1MO"),
+            "Yearly on the 2nd to last Monday and the 1st Monday of the year"
+        );
+        assert_eq!(
+            text("DTSTART;VALUE=DATE:20200131\nRRULE:FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13"),
+            "Monthly on Friday the 13th"
+        );
+    }
+
+    #[test]
+    fn german() {
+        let text = |s: &str| s.parse::<RRule>().unwrap().to_text_with(&German);
+
+        assert_eq!(
+            text("DTSTART:20200106T100000\nRRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=WE,MO;COUNT=10"),
+            "Alle 2 Wochen am Montag und Mittwoch um 10:00 Uhr, 10 Mal"
+        );
+        assert_eq!(
+            text("DTSTART;VALUE=DATE:20200131\nRRULE:FREQ=MONTHLY;BYDAY=-1FR;UNTIL=20251231"),
+            "Monatlich am letzten Freitag bis 31.12.2025"
+        );
+        assert_eq!(
+            text("DTSTART:20200131T170000Z\nRRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1"),
+            "Monatlich am letzten Werktag um 17:00 Uhr"
+        );
+    }
+
+    #[test]
+    fn by_set_pos() {
+        assert_eq!(
+            text("DTSTART:20200131T170000Z\nRRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1"),
+            "Monthly on the last weekday at 17:00"
+        );
+        assert_eq!(
+            text("DTSTART;VALUE=DATE:20200101\nRRULE:FREQ=MONTHLY;BYMONTHDAY=1,15;BYSETPOS=2"),
+            "Monthly on the 1st and 15th, only the 2nd occurrence in each month"
+        );
+    }
+}
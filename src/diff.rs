@@ -0,0 +1,220 @@
+use crate::freq::Frequency;
+use crate::iter::RRuleIterYield;
+use crate::rrule::RRule;
+use crate::rrule_set::RRuleSet;
+use crate::weekday::Weekday;
+use alloc::{vec, vec::Vec};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+/// An occurrence which moved to another time within its period
+#[derive(Debug, Clone, Copy)]
+pub struct Moved {
+    pub from: RRuleIterYield,
+    pub to: RRuleIterYield,
+}
+
+/// Difference between the occurrences of two versions of a rule set
+#[derive(Debug, Clone, Default)]
+pub struct OccurrenceDiff {
+    /// Occurrences only yielded by the new version
+    pub added: Vec<RRuleIterYield>,
+    /// Occurrences only yielded by the old version
+    pub removed: Vec<RRuleIterYield>,
+    /// Removed occurrences paired with an added occurrence of the same period
+    pub moved: Vec<Moved>,
+}
+
+impl OccurrenceDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+    }
+}
+
+impl RRuleSet {
+    /// Compare the occurrences of two versions of the set from `start` (inclusive) to `end`
+    /// (exclusive)
+    ///
+    /// `start` and `end` are UTC for sets with a timezone and local time for floating ones.
+    /// A removed occurrence is paired with the closest added occurrence of the same period as
+    /// [moved](OccurrenceDiff::moved). The period is that of the finest frequency of the rules of
+    /// either version, e.g. the week of weekly rules.
+    pub fn diff(&self, new: &RRuleSet, start: NaiveDateTime, end: NaiveDateTime) -> OccurrenceDiff {
+        let window = |set: &RRuleSet| {
+            set.iter()
+                .skip_while(move |occurrence| occurrence.sort_key() < start)
+                .take_while(move |occurrence| occurrence.sort_key() < end)
+                .peekable()
+        };
+
+        let mut old_iter = window(self);
+        let mut new_iter = window(new);
+
+        let mut removed = vec![];
+        let mut added = vec![];
+
+        loop {
+            match (old_iter.peek(), new_iter.peek()) {
+                (None, None) => break,
+                (Some(_), None) => removed.extend(old_iter.next()),
+                (None, Some(_)) => added.extend(new_iter.next()),
+                (Some(old), Some(new)) => {
+                    let (old, new) = (old.sort_key(), new.sort_key());
+
+                    if old < new {
+                        removed.extend(old_iter.next());
+                    } else if new < old {
+                        added.extend(new_iter.next());
+                    } else {
+                        old_iter.next();
+                        new_iter.next();
+                    }
+                }
+            }
+        }
+
+        let period = Period::new(self, new);
+        let mut diff = OccurrenceDiff::default();
+
+        for from in removed {
+            let key = period.key(from.local());
+
+            // The closest occurrence of the same period is most likely the moved one
+            let closest = added
+                .iter()
+                .enumerate()
+                .filter(|(_, to)| period.key(to.local()) == key)
+                .min_by_key(|(_, to)| (to.sort_key() - from.sort_key()).abs())
+                .map(|(i, _)| i);
+
+            match closest {
+                Some(i) => diff.moved.push(Moved {
+                    from,
+                    to: added.remove(i),
+                }),
+                None => diff.removed.push(from),
+            }
+        }
+
+        diff.added = added;
+        diff
+    }
+}
+
+impl RRule {
+    /// Compare the occurrences of two versions of the rule, see [`RRuleSet::diff`]
+    pub fn diff(&self, new: &RRule, start: NaiveDateTime, end: NaiveDateTime) -> OccurrenceDiff {
+        let set = |rrule: &RRule| RRuleSet {
+            rrules: vec![rrule.recur.clone()],
+            ..RRuleSet::new(rrule.dt_start)
+        };
+
+        set(self).diff(&set(new), start, end)
+    }
+}
+
+/// Period of the rules in which occurrences are considered moved
+struct Period {
+    freq: Frequency,
+    week_start: Weekday,
+}
+
+impl Period {
+    fn new(old: &RRuleSet, new: &RRuleSet) -> Self {
+        let recurs = || old.rrules.iter().chain(&new.rrules);
+
+        let freq = recurs()
+            .map(|recur| recur.freq)
+            .min_by_key(|&freq| freq_rank(freq))
+            .unwrap_or(Frequency::Daily);
+
+        let week_start = recurs()
+            .find_map(|recur| recur.week_start)
+            .unwrap_or(Weekday::Monday);
+
+        Self { freq, week_start }
+    }
+
+    /// Start of the period `datetime` is in
+    fn key(&self, datetime: NaiveDateTime) -> NaiveDateTime {
+        let date = datetime.date();
+        let time = |h, m, s| date.and_time(NaiveTime::from_hms_opt(h, m, s).expect("valid time"));
+
+        match self.freq {
+            Frequency::Secondly => datetime,
+            Frequency::Minutely => time(datetime.hour(), datetime.minute(), 0),
+            Frequency::Hourly => time(datetime.hour(), 0, 0),
+            Frequency::Daily => time(0, 0, 0),
+            Frequency::Weekly => {
+                let days = self.week_start.days_until(date.weekday()) as i64;
+                (date - Duration::days(days)).and_time(NaiveTime::MIN)
+            }
+            Frequency::Monthly => start_of(date.year(), date.month()),
+            Frequency::Yearly => start_of(date.year(), 1),
+        }
+    }
+}
+
+fn start_of(year: i32, month: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .expect("valid date")
+        .and_time(NaiveTime::MIN)
+}
+
+fn freq_rank(freq: Frequency) -> u8 {
+    match freq {
+        Frequency::Secondly => 0,
+        Frequency::Minutely => 1,
+        Frequency::Hourly => 2,
+        Frequency::Daily => 3,
+        Frequency::Weekly => 4,
+        Frequency::Monthly => 5,
+        Frequency::Yearly => 6,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::{String, ToString};
+
+    fn datetime(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%S").unwrap()
+    }
+
+    fn local(occurrences: &[RRuleIterYield]) -> Vec<String> {
+        occurrences
+            .iter()
+            .map(|occurrence| occurrence.local().format("%Y%m%dT%H%M").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn diff() {
+        let old: RRule = "DTSTART:20220103T090000\nRRULE:FREQ=WEEKLY;BYDAY=MO,WE"
+            .parse()
+            .unwrap();
+        let new: RRule = "DTSTART:20220103T090000\nRRULE:FREQ=WEEKLY;BYDAY=MO,TH;BYHOUR=9,10"
+            .parse()
+            .unwrap();
+
+        let diff = old.diff(
+            &new,
+            datetime("20220103T000000"),
+            datetime("20220110T000000"),
+        );
+
+        assert_eq!(local(&diff.added), ["20220103T1000", "20220106T1000"]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.moved.len(), 1);
+        assert_eq!(local(&[diff.moved[0].from]), ["20220105T0900"]);
+        assert_eq!(local(&[diff.moved[0].to]), ["20220106T0900"]);
+
+        assert!(old
+            .diff(
+                &old,
+                datetime("20220103T000000"),
+                datetime("20230103T000000")
+            )
+            .is_empty());
+    }
+}
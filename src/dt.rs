@@ -1,13 +1,13 @@
 use crate::error::IResult;
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use core::fmt;
+use core::str::FromStr;
 use nom::bytes::complete::take;
 use nom::character::complete::char;
 use nom::combinator::{map, map_res, opt};
 use nom::sequence::{preceded, tuple};
-use std::fmt;
-use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Dt {
     Date(NaiveDate),
     DateTimeLocal(NaiveDateTime),
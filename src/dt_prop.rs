@@ -1,7 +1,10 @@
 use crate::dt::Dt;
-use crate::error::{IResult, ParseError};
-use chrono::{DateTime, NaiveDateTime, TimeZone};
-use chrono_tz::Tz;
+use crate::error::IResult;
+use crate::iter::RRuleIterYield;
+use crate::zone::{DefaultZone, Zone};
+use alloc::string::String;
+use chrono::{DateTime, NaiveDateTime, NaiveTime};
+use core::fmt;
 use nom::branch::alt;
 use nom::bytes::complete::{tag, tag_no_case, take_while1};
 use nom::character::complete::char;
@@ -9,30 +12,36 @@ use nom::combinator::{cut, map, map_res};
 use nom::error::context;
 use nom::multi::many0;
 use nom::sequence::{preceded, tuple};
-use std::fmt;
-use std::str::FromStr;
+
+#[derive(Debug, thiserror::Error)]
+#[error("unknown timezone '{0}'")]
+struct UnknownTimezone(String);
 
 #[derive(Debug, Clone, Copy)]
-enum DtParam {
+enum DtParam<Z> {
     ValueDate,
     ValueDateTime,
-    Tz(Tz),
+    Tz(Z),
+    RangeThisAndFuture,
 }
 
-impl DtParam {
+impl<Z: Zone> DtParam<Z> {
     fn parse(i: &str) -> IResult<&str, Self> {
         alt((
             map(
                 preceded(
                     tag_no_case("TZID="),
                     cut(map_res(take_while1(|c| c != ';' && c != ':'), |tz| {
-                        Tz::from_str(tz).map_err(ParseError::from_tz_parse)
+                        Z::from_tzid(tz).ok_or_else(|| UnknownTimezone(tz.into()))
                     })),
                 ),
                 Self::Tz,
             ),
             map(tag_no_case("VALUE=DATE"), |_| Self::ValueDate),
             map(tag_no_case("VALUE=DATETIME"), |_| Self::ValueDateTime),
+            map(tag_no_case("RANGE=THISANDFUTURE"), |_| {
+                Self::RangeThisAndFuture
+            }),
         ))(i)
     }
 }
@@ -41,51 +50,85 @@ impl DtParam {
 pub enum DtPropertyParseError {
     #[error("duplicate parameter {0}")]
     DuplicateParam(&'static str),
+    #[error("unexpected parameter {0}")]
+    UnexpectedParam(&'static str),
     #[error("failed to convert floating datetime")]
     InvalidFloatingTime,
     #[error("value parameter specified {0} (default=DATETIME) but got {1}")]
     InvalidValueParam(&'static str, &'static str),
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct DtProperty {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DtProperty<Z: Zone = DefaultZone> {
     pub dt: Dt,
-    pub tz: Option<Tz>,
+    pub tz: Option<Z>,
 }
 
 /// See [RFC5545#3.3.5] FORM #3: DATE WITH LOCAL TIME AND TIME ZONE REFERENCE
 ///
 /// [RFC5545#3.3.5]((https://datatracker.ietf.org/doc/html/rfc5545#section-3.3.5))
-pub(crate) fn local_datetime_with_tz(datetime: NaiveDateTime, tz: Tz) -> DateTime<Tz> {
+pub(crate) fn local_datetime_with_tz<Z: Zone>(datetime: NaiveDateTime, tz: Z) -> DateTime<Z> {
     if let Some(datetime) = tz.from_local_datetime(&datetime).earliest() {
         datetime
     } else {
-        DateTime::from_utc(datetime, tz.offset_from_utc_datetime(&datetime))
+        tz.from_utc_datetime(&datetime)
     }
 }
 
-impl DtProperty {
-    pub(crate) fn to_datetime(self) -> DateTime<Tz> {
-        let tz = self.tz.unwrap_or(Tz::UTC);
+impl<Z: Zone> DtProperty<Z> {
+    pub(crate) fn to_datetime(self) -> DateTime<Z> {
+        let tz = self.tz.unwrap_or_else(Z::utc);
 
         match self.dt {
-            Dt::Date(date) => local_datetime_with_tz(date.and_hms(0, 0, 0), tz),
+            Dt::Date(date) => local_datetime_with_tz(date.and_time(NaiveTime::MIN), tz),
             Dt::DateTimeLocal(datetime) => local_datetime_with_tz(datetime, tz),
             Dt::DateTimeUtc(datetime) => datetime.with_timezone(&tz),
         }
     }
 
-    pub(crate) fn result_tz(self) -> Option<Tz> {
+    pub(crate) fn result_tz(self) -> Option<Z> {
         if self.tz.is_some() {
             self.tz
         } else if let Dt::DateTimeUtc(_) = self.dt {
-            Some(Tz::UTC)
+            Some(Z::utc())
         } else {
             None
         }
     }
 
+    /// Property at the occurrence, of the same value type and timezone as this property
+    pub(crate) fn at(self, occurrence: RRuleIterYield<Z>) -> Self {
+        let local = match (occurrence, self.tz) {
+            (RRuleIterYield::DateTimeTz(datetime), Some(tz)) => {
+                datetime.with_timezone(&tz).naive_local()
+            }
+            _ => occurrence.local(),
+        };
+
+        let dt = match self.dt {
+            Dt::Date(_) => Dt::Date(local.date()),
+            Dt::DateTimeLocal(_) => Dt::DateTimeLocal(local),
+            Dt::DateTimeUtc(_) => Dt::DateTimeUtc(occurrence.sort_key().and_utc()),
+        };
+
+        Self { dt, ..self }
+    }
+
     fn parse(i: &str) -> IResult<&str, Self> {
+        map_res(
+            Self::parse_with_range,
+            |(property, this_and_future)| -> Result<Self, DtPropertyParseError> {
+                if this_and_future {
+                    return Err(DtPropertyParseError::UnexpectedParam("RANGE"));
+                }
+
+                Ok(property)
+            },
+        )(i)
+    }
+
+    /// Parse the property and whether it has the RANGE=THISANDFUTURE parameter
+    fn parse_with_range(i: &str) -> IResult<&str, (Self, bool)> {
         context(
             "invalid dt property",
             map_res(
@@ -93,9 +136,10 @@ impl DtProperty {
                     many0(preceded(char(';'), cut(DtParam::parse))),
                     preceded(char(':'), Dt::parse),
                 )),
-                |(params, dt)| -> Result<Self, DtPropertyParseError> {
+                |(params, dt)| -> Result<(Self, bool), DtPropertyParseError> {
                     let mut is_datetime = None;
                     let mut tz = None;
+                    let mut this_and_future = false;
 
                     for param in params {
                         match param {
@@ -120,6 +164,13 @@ impl DtProperty {
 
                                 tz = Some(t);
                             }
+                            DtParam::RangeThisAndFuture => {
+                                if this_and_future {
+                                    return Err(DtPropertyParseError::DuplicateParam("RANGE"));
+                                }
+
+                                this_and_future = true;
+                            }
                         }
                     }
 
@@ -151,17 +202,17 @@ impl DtProperty {
                         }
                     }
 
-                    Ok(Self { dt, tz })
+                    Ok((Self { dt, tz }, this_and_future))
                 },
             ),
         )(i)
     }
 }
 
-impl fmt::Display for DtProperty {
+impl<Z: Zone> fmt::Display for DtProperty<Z> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Some(tz) = self.tz {
-            write!(f, ";TZID={}", tz)?;
+            write!(f, ";TZID={}", tz.tzid())?;
         }
 
         if self.dt.is_date() {
@@ -172,28 +223,68 @@ impl fmt::Display for DtProperty {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct DtStart(pub DtProperty);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DtStart<Z: Zone = DefaultZone>(pub DtProperty<Z>);
 
-impl DtStart {
+impl<Z: Zone> DtStart<Z> {
     pub fn parse(i: &str) -> IResult<&str, Self> {
         map(preceded(tag("DTSTART"), DtProperty::parse), Self)(i)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct RDate(pub DtProperty);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DtEnd<Z: Zone = DefaultZone>(pub DtProperty<Z>);
+
+impl<Z: Zone> DtEnd<Z> {
+    pub fn parse(i: &str) -> IResult<&str, Self> {
+        map(preceded(tag("DTEND"), DtProperty::parse), Self)(i)
+    }
+}
+
+/// RECURRENCE-ID, with `this_and_future` set by the `RANGE=THISANDFUTURE` parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecurrenceId<Z: Zone = DefaultZone> {
+    pub dt: DtProperty<Z>,
+    pub this_and_future: bool,
+}
+
+impl<Z: Zone> RecurrenceId<Z> {
+    pub fn parse(i: &str) -> IResult<&str, Self> {
+        map(
+            preceded(tag("RECURRENCE-ID"), DtProperty::parse_with_range),
+            |(dt, this_and_future)| Self {
+                dt,
+                this_and_future,
+            },
+        )(i)
+    }
+}
 
-impl RDate {
+impl<Z: Zone> fmt::Display for RecurrenceId<Z> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RECURRENCE-ID")?;
+
+        if self.this_and_future {
+            write!(f, ";RANGE=THISANDFUTURE")?;
+        }
+
+        write!(f, "{}", self.dt)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RDate<Z: Zone = DefaultZone>(pub DtProperty<Z>);
+
+impl<Z: Zone> RDate<Z> {
     pub fn parse(i: &str) -> IResult<&str, Self> {
         map(preceded(tag("RDATE"), DtProperty::parse), Self)(i)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct ExDate(pub DtProperty);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExDate<Z: Zone = DefaultZone>(pub DtProperty<Z>);
 
-impl ExDate {
+impl<Z: Zone> ExDate<Z> {
     pub fn parse(i: &str) -> IResult<&str, Self> {
         map(preceded(tag("EXDATE"), DtProperty::parse), Self)(i)
     }
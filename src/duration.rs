@@ -0,0 +1,224 @@
+use crate::dt_prop::local_datetime_with_tz;
+use crate::error::{IResult, ParseError};
+use crate::iter::RRuleIterYield;
+use crate::util::parse_u32;
+use crate::zone::Zone;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+use nom::character::complete::{char, one_of};
+use nom::combinator::{map, opt, verify};
+use nom::sequence::{pair, preceded, terminated, tuple};
+use nom::Finish;
+
+/// DURATION value of [RFC5545#3.3.6], e.g. `P1D`, `PT1H30M` or `-P2W`
+///
+/// Weeks and days are nominal and follow the wall clock across DST transitions, hours, minutes
+/// and seconds are exact.
+///
+/// [RFC5545#3.3.6]: https://datatracker.ietf.org/doc/html/rfc5545#section-3.3.6
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Duration {
+    pub negative: bool,
+    pub weeks: u32,
+    pub days: u32,
+    pub hours: u32,
+    pub minutes: u32,
+    pub seconds: u32,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DurationFromStrError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("some input was not consumed: '{0}'")]
+    LeftOver(String),
+}
+
+impl Duration {
+    pub fn parse(i: &str) -> IResult<&str, Self> {
+        let component = |designator| opt(terminated(parse_u32, char(designator)));
+
+        map(
+            pair(
+                opt(one_of("+-")),
+                preceded(
+                    char('P'),
+                    verify(
+                        tuple((
+                            component('W'),
+                            component('D'),
+                            opt(preceded(
+                                char('T'),
+                                verify(
+                                    tuple((component('H'), component('M'), component('S'))),
+                                    |time| *time != (None, None, None),
+                                ),
+                            )),
+                        )),
+                        |date| *date != (None, None, None),
+                    ),
+                ),
+            ),
+            |(sign, (weeks, days, time))| {
+                let (hours, minutes, seconds) = time.unwrap_or_default();
+
+                Self {
+                    negative: sign == Some('-'),
+                    weeks: weeks.unwrap_or_default(),
+                    days: days.unwrap_or_default(),
+                    hours: hours.unwrap_or_default(),
+                    minutes: minutes.unwrap_or_default(),
+                    seconds: seconds.unwrap_or_default(),
+                }
+            },
+        )(i)
+    }
+
+    /// Nominal part in days
+    fn nominal_days(&self) -> i64 {
+        let days = i64::from(self.weeks) * 7 + i64::from(self.days);
+
+        if self.negative {
+            -days
+        } else {
+            days
+        }
+    }
+
+    /// Exact part in seconds
+    fn exact_seconds(&self) -> i64 {
+        let seconds =
+            i64::from(self.hours) * 3600 + i64::from(self.minutes) * 60 + i64::from(self.seconds);
+
+        if self.negative {
+            -seconds
+        } else {
+            seconds
+        }
+    }
+
+    /// Add the duration to an occurrence, `None` if the result is out of range
+    ///
+    /// The nominal part is added to the local time of the occurrence, then the exact part is
+    /// added to the resulting instant.
+    pub fn add_to<Z: Zone>(&self, occurrence: RRuleIterYield<Z>) -> Option<RRuleIterYield<Z>> {
+        let days = chrono::Duration::try_days(self.nominal_days())?;
+        let seconds = chrono::Duration::try_seconds(self.exact_seconds())?;
+
+        match occurrence {
+            RRuleIterYield::DateTimeLocal(datetime) => datetime
+                .checked_add_signed(days)?
+                .checked_add_signed(seconds)
+                .map(RRuleIterYield::DateTimeLocal),
+            RRuleIterYield::DateTimeTz(datetime) => {
+                let local = datetime.naive_local().checked_add_signed(days)?;
+
+                local_datetime_with_tz(local, datetime.timezone())
+                    .checked_add_signed(seconds)
+                    .map(RRuleIterYield::DateTimeTz)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            f.write_str("-")?;
+        }
+
+        f.write_str("P")?;
+
+        if self.weeks != 0 {
+            write!(f, "{}W", self.weeks)?;
+        }
+
+        if self.days != 0 {
+            write!(f, "{}D", self.days)?;
+        }
+
+        if self.hours != 0 || self.minutes != 0 || self.seconds != 0 {
+            f.write_str("T")?;
+
+            if self.hours != 0 {
+                write!(f, "{}H", self.hours)?;
+            }
+
+            if self.minutes != 0 {
+                write!(f, "{}M", self.minutes)?;
+            }
+
+            if self.seconds != 0 {
+                write!(f, "{}S", self.seconds)?;
+            }
+        } else if self.weeks == 0 && self.days == 0 {
+            f.write_str("T0S")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Duration {
+    type Err = DurationFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (rem, duration) = Self::parse(s).finish().map_err(|e| e.locate(s))?;
+
+        if rem.is_empty() {
+            Ok(duration)
+        } else {
+            Err(DurationFromStrError::LeftOver(rem.into()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parse_and_display() {
+        for s in ["P1D", "PT1H30M", "-P2W", "P1DT12H", "PT0S"] {
+            assert_eq!(s.parse::<Duration>().unwrap().to_string(), s);
+        }
+
+        assert!("P1Y".parse::<Duration>().is_err());
+        assert!("P1DT".parse::<Duration>().is_err());
+        assert!("P".parse::<Duration>().is_err());
+        assert!("-P".parse::<Duration>().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn nominal_and_exact() {
+        use chrono::{NaiveDate, TimeZone};
+        use chrono_tz::Europe::Berlin;
+
+        // The night from 26th to 27th of March 2022 is one hour shorter in Berlin
+        let start = RRuleIterYield::DateTimeTz(
+            Berlin
+                .from_local_datetime(
+                    &NaiveDate::from_ymd_opt(2022, 3, 26)
+                        .unwrap()
+                        .and_hms_opt(9, 0, 0)
+                        .unwrap(),
+                )
+                .unwrap(),
+        );
+
+        let end = |s: &str| {
+            s.parse::<Duration>()
+                .unwrap()
+                .add_to(start)
+                .unwrap()
+                .local()
+                .to_string()
+        };
+
+        assert_eq!(end("P1D"), "2022-03-27 09:00:00");
+        assert_eq!(end("PT24H"), "2022-03-27 10:00:00");
+    }
+}
@@ -0,0 +1,149 @@
+use crate::dt_prop::{ExDate, RDate};
+use crate::iter::RRuleIterYield;
+use crate::rrule_set::RRuleSet;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EditError {
+    #[error("{0:?} is not an occurrence of the set")]
+    NotAnOccurrence(RRuleIterYield),
+    #[error("{0:?} already is an occurrence of the set")]
+    AlreadyAnOccurrence(RRuleIterYield),
+}
+
+impl RRuleSet {
+    /// Check if the set yields the occurrence
+    pub fn contains(&self, occurrence: RRuleIterYield) -> bool {
+        let key = occurrence.sort_key();
+
+        self.iter()
+            .find(|yielded| yielded.sort_key() >= key)
+            .is_some_and(|yielded| yielded.sort_key() == key)
+    }
+
+    /// Exclude a single occurrence by an EXDATE of the same value type and timezone as DTSTART
+    pub fn exclude(&mut self, occurrence: RRuleIterYield) -> Result<(), EditError> {
+        if !self.contains(occurrence) {
+            return Err(EditError::NotAnOccurrence(occurrence));
+        }
+
+        self.exdates.push(ExDate(self.dt_start.0.at(occurrence)));
+        Ok(())
+    }
+
+    /// Add a single occurrence by an RDATE of the same value type and timezone as DTSTART
+    pub fn add(&mut self, occurrence: RRuleIterYield) -> Result<(), EditError> {
+        // All-day sets truncate the occurrence to its date, which may be an occurrence already
+        let dt = self.dt_start.0.at(occurrence);
+
+        if self.contains(RRuleIterYield::from_property(dt, self.dt_start.0.tz)) {
+            return Err(EditError::AlreadyAnOccurrence(occurrence));
+        }
+
+        self.rdates.push(RDate(dt));
+        Ok(())
+    }
+
+    /// Replace a single occurrence with one at another time
+    pub fn move_occurrence(
+        &mut self,
+        occurrence: RRuleIterYield,
+        to: RRuleIterYield,
+    ) -> Result<(), EditError> {
+        if !self.contains(occurrence) {
+            return Err(EditError::NotAnOccurrence(occurrence));
+        }
+
+        self.add(to)?;
+        self.exdates.push(ExDate(self.dt_start.0.at(occurrence)));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+    use chrono::NaiveDate;
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn edit() {
+        use chrono::{TimeZone, Utc};
+
+        let mut set: RRuleSet = "DTSTART;TZID=Europe/Berlin:20220103T090000\n\
+            RRULE:FREQ=WEEKLY;COUNT=4"
+            .parse()
+            .unwrap();
+
+        let occurrences: Vec<_> = set.iter().collect();
+        let utc = |d, h| {
+            RRuleIterYield::DateTimeTz(
+                Utc.with_ymd_and_hms(2022, 1, d, h, 0, 0)
+                    .unwrap()
+                    .with_timezone(&chrono_tz::UTC),
+            )
+        };
+
+        set.exclude(occurrences[1]).unwrap();
+        set.move_occurrence(occurrences[2], utc(18, 9)).unwrap();
+
+        assert_eq!(
+            set.to_string(),
+            "DTSTART;TZID=Europe/Berlin:20220103T090000\n\
+             RRULE:FREQ=WEEKLY;COUNT=4\n\
+             RDATE;TZID=Europe/Berlin:20220118T100000\n\
+             EXDATE;TZID=Europe/Berlin:20220110T090000\n\
+             EXDATE;TZID=Europe/Berlin:20220117T090000"
+        );
+
+        let local: Vec<_> = set
+            .iter()
+            .map(|occurrence| occurrence.local().format("%d %H:%M").to_string())
+            .collect();
+        assert_eq!(local, ["03 09:00", "18 10:00", "24 09:00"]);
+
+        assert!(matches!(
+            set.exclude(occurrences[1]),
+            Err(EditError::NotAnOccurrence(_))
+        ));
+        assert!(matches!(
+            set.add(occurrences[0]),
+            Err(EditError::AlreadyAnOccurrence(_))
+        ));
+    }
+
+    #[test]
+    fn edit_all_day() {
+        let mut set: RRuleSet = "DTSTART;VALUE=DATE:20220103\nRRULE:FREQ=DAILY;COUNT=5"
+            .parse()
+            .unwrap();
+
+        let occurrences: Vec<_> = set.iter().collect();
+        let at = |d, h| {
+            RRuleIterYield::DateTimeLocal(
+                NaiveDate::from_ymd_opt(2022, 1, d)
+                    .unwrap()
+                    .and_hms_opt(h, 0, 0)
+                    .unwrap(),
+            )
+        };
+
+        // The 7th at 10:00 is stored as the 7th, which is an occurrence already
+        assert!(matches!(
+            set.move_occurrence(occurrences[1], at(7, 10)),
+            Err(EditError::AlreadyAnOccurrence(_))
+        ));
+        assert_eq!(set.iter().count(), 5);
+
+        set.move_occurrence(occurrences[1], at(8, 10)).unwrap();
+
+        assert_eq!(
+            set.to_string(),
+            "DTSTART;VALUE=DATE:20220103\n\
+             RRULE:FREQ=DAILY;COUNT=5\n\
+             RDATE;VALUE=DATE:20220108\n\
+             EXDATE;VALUE=DATE:20220104"
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use crate::freq::Frequency;
+use crate::iter::{RRuleIter, RRuleIterYield};
+use crate::recur::Recur;
+use crate::rrule::RRule;
+use chrono::{Duration, NaiveDateTime};
+
+/// Number of occurrences [`RRule::equivalent`] compares at most
+pub const DEFAULT_HORIZON: usize = 500_000;
+
+/// The Gregorian calendar repeats itself, including weekdays, every 400 years
+const GREGORIAN_CYCLE_DAYS: i64 = 146_097;
+const GREGORIAN_CYCLE_MONTHS: i64 = 4_800;
+
+const DAY: i64 = 86_400;
+const WEEK: i64 = 7 * DAY;
+
+/// Result of comparing the occurrences of two rules
+#[derive(Debug, Clone, Copy)]
+pub enum Equivalence {
+    /// Both rules yield the same occurrences
+    Equivalent,
+    /// The given occurrence is yielded by only one of the rules, it is the first such occurrence
+    Different(RRuleIterYield),
+    /// The rules agree on all compared occurrences but their equivalence could not be decided
+    Undecided,
+}
+
+impl Equivalence {
+    pub fn is_equivalent(&self) -> bool {
+        matches!(self, Self::Equivalent)
+    }
+}
+
+impl RRule {
+    /// Check if both rules yield the same occurrences, see [`RRule::equivalent_within`]
+    pub fn equivalent(&self, other: &RRule) -> Equivalence {
+        self.equivalent_within(other, DEFAULT_HORIZON)
+    }
+
+    /// Check if both rules yield the same occurrences, comparing at most `horizon` occurrences
+    ///
+    /// Finite rules are compared until both end. Unbounded rules of the same timezone repeat
+    /// after a cycle, derived from their frequencies, intervals and the calendar features they
+    /// depend on, and are compared over their common cycle. If the comparison needs more than
+    /// `horizon` occurrences the result is [`Equivalence::Undecided`].
+    pub fn equivalent_within(&self, other: &RRule, horizon: usize) -> Equivalence {
+        match (self.is_empty(), other.is_empty()) {
+            (true, true) => return Equivalence::Equivalent,
+            (true, false) => return first_difference(other),
+            (false, true) => return first_difference(self),
+            (false, false) => {}
+        }
+
+        let window_end = self.common_cycle_end(other);
+
+        let mut a = RRuleIter::new(self).peekable();
+        let mut b = RRuleIter::new(other).peekable();
+
+        for _ in 0..horizon {
+            let (x, y) = match (a.peek(), b.peek()) {
+                (None, None) => return Equivalence::Equivalent,
+                (Some(x), None) | (None, Some(x)) => return Equivalence::Different(*x),
+                (Some(x), Some(y)) => (*x, *y),
+            };
+
+            if window_end.is_some_and(|end| x.local() > end && y.local() > end) {
+                return Equivalence::Equivalent;
+            }
+
+            if x.sort_key() != y.sort_key() {
+                return Equivalence::Different(if x.sort_key() < y.sort_key() { x } else { y });
+            }
+
+            a.next();
+            b.next();
+        }
+
+        Equivalence::Undecided
+    }
+
+    /// Rules which can never have an occurrence, the iterator may not terminate for these
+    fn is_empty(&self) -> bool {
+        self.recur.count == Some(0) || !self.month_days_exist()
+    }
+
+    /// End of the local time window after which both rules repeat their comparison so far
+    ///
+    /// Past the later DTSTART both rules are periodic with their common cycle, so comparing
+    /// one more cycle decides their equivalence. `None` if the rules are bounded, in different
+    /// timezones or the cycle is out of range.
+    fn common_cycle_end(&self, other: &RRule) -> Option<NaiveDateTime> {
+        let bounded = |rrule: &RRule| rrule.recur.count.is_some() || rrule.recur.until.is_some();
+
+        if bounded(self)
+            || bounded(other)
+            || self.dt_start.0.result_tz() != other.dt_start.0.result_tz()
+        {
+            return None;
+        }
+
+        let cycle = lcm(self.recur.cycle()?, other.recur.cycle()?)?;
+
+        let dt_start = |rrule: &RRule| rrule.dt_start.0.to_datetime().naive_local();
+        let later = dt_start(self).max(dt_start(other));
+
+        later.checked_add_signed(Duration::try_seconds(cycle)?)
+    }
+}
+
+impl Recur {
+    /// Length in seconds after which the occurrences of the rule repeat
+    pub(crate) fn cycle(&self) -> Option<i64> {
+        let recur = self;
+        let interval = i64::from(recur.interval.unwrap_or(1).max(1));
+
+        let period_months = match recur.freq {
+            Frequency::Monthly => Some(interval),
+            Frequency::Yearly => Some(12 * interval),
+            _ => None,
+        };
+
+        let depends_on_calendar = period_months.is_some()
+            || !recur.by_month.is_empty()
+            || !recur.by_month_day.is_empty()
+            || !recur.by_year_day.is_empty()
+            || !recur.by_week_no.is_empty();
+
+        if let Some(months) = period_months {
+            let cycles = lcm(months, GREGORIAN_CYCLE_MONTHS)? / GREGORIAN_CYCLE_MONTHS;
+            return cycles.checked_mul(GREGORIAN_CYCLE_DAYS * DAY);
+        }
+
+        let period = match recur.freq {
+            Frequency::Secondly => interval,
+            Frequency::Minutely => 60 * interval,
+            Frequency::Hourly => 3600 * interval,
+            Frequency::Daily => DAY * interval,
+            _ => WEEK * interval,
+        };
+
+        let mut cycle = period;
+
+        if !recur.by_hour.is_empty() || !recur.by_minute.is_empty() || !recur.by_second.is_empty() {
+            cycle = lcm(cycle, DAY)?;
+        }
+
+        if !recur.by_day.is_empty() {
+            cycle = lcm(cycle, WEEK)?;
+        }
+
+        if depends_on_calendar {
+            cycle = lcm(cycle, GREGORIAN_CYCLE_DAYS * DAY)?;
+        }
+
+        Some(cycle)
+    }
+}
+
+fn first_difference(rrule: &RRule) -> Equivalence {
+    match RRuleIter::new(rrule).next() {
+        Some(occurrence) => Equivalence::Different(occurrence),
+        None => Equivalence::Equivalent,
+    }
+}
+
+pub(crate) fn lcm(a: i64, b: i64) -> Option<i64> {
+    let gcd = |mut a: i64, mut b: i64| {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    };
+
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+#[cfg(test)]
+#[cfg(feature = "chrono-tz")]
+mod tests {
+    use super::*;
+
+    fn equivalent(a: &str, b: &str) -> Equivalence {
+        let dt_start = "DTSTART;TZID=Europe/Berlin:20220103T090000\nRRULE:";
+        let a: RRule = format!("{dt_start}{a}").parse().unwrap();
+        let b: RRule = format!("{dt_start}{b}").parse().unwrap();
+
+        a.equivalent_within(&b, 1000)
+    }
+
+    #[test]
+    fn equivalent_rules() {
+        assert!(equivalent("FREQ=DAILY;BYDAY=MO", "FREQ=WEEKLY;BYDAY=MO").is_equivalent());
+        assert!(equivalent("FREQ=WEEKLY;INTERVAL=2", "FREQ=DAILY;INTERVAL=14").is_equivalent());
+        assert!(
+            equivalent("FREQ=DAILY;COUNT=3", "FREQ=DAILY;UNTIL=20220105T080000Z").is_equivalent()
+        );
+    }
+
+    #[test]
+    fn different_rules() {
+        match equivalent("FREQ=DAILY;BYDAY=MO,TU", "FREQ=WEEKLY;BYDAY=MO") {
+            Equivalence::Different(RRuleIterYield::DateTimeTz(datetime)) => {
+                assert_eq!(datetime.to_string(), "2022-01-04 09:00:00 CET")
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        assert!(matches!(
+            equivalent("FREQ=DAILY;COUNT=3", "FREQ=DAILY"),
+            Equivalence::Different(_)
+        ));
+    }
+
+    #[test]
+    fn undecided() {
+        // The monthly rule repeats only after 400 years
+        assert!(matches!(
+            equivalent(
+                "FREQ=MONTHLY;BYMONTHDAY=3",
+                "FREQ=MONTHLY;BYDAY=1MO,1TU,1WE,1TH,1FR,1SA,1SU;BYMONTHDAY=3"
+            ),
+            Equivalence::Undecided
+        ));
+    }
+}
@@ -1,20 +1,180 @@
-use std::fmt;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use core::ops::Range;
 
 pub type IResult<I, O> = nom::IResult<I, O, ParseError>;
 
+/// What went wrong at the location of a [`ParseError`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// One of the listed tokens was expected
+    Expected(&'static [&'static str]),
+    /// A number was expected or it is out of range
+    InvalidNumber,
+    /// The value is well formed but not valid, e.g. the 30th of February
+    InvalidValue(String),
+    /// A value was followed by unexpected characters
+    Unexpected,
+    Other(nom::error::ErrorKind),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Expected(tokens) => write!(f, "expected one of {}", tokens.join(", ")),
+            Self::InvalidNumber => f.write_str("invalid number"),
+            Self::InvalidValue(msg) => f.write_str(msg),
+            Self::Unexpected => f.write_str("unexpected characters"),
+            Self::Other(kind) => write!(f, "failed to parse ({})", kind.description()),
+        }
+    }
+}
+
+/// Error of the nom parsers
+///
+/// Besides the trace of messages, which is its `Display` representation, the error describes the
+/// innermost failure: its [kind](Self::kind), the [rule part](Self::rule_part) being parsed and,
+/// once [located](Self::locate) in the parsed input, its [span](Self::span).
 pub struct ParseError {
     errors: Vec<String>,
+    kind: ParseErrorKind,
+    part: Option<&'static str>,
+    // Length of the offending token at `addr`
+    len: usize,
+    addr: usize,
+    offset: Option<usize>,
 }
 
 impl ParseError {
-    pub(crate) fn from_tz_parse(msg: String) -> Self {
-        Self { errors: vec![msg] }
+    fn new(i: &str, kind: ParseErrorKind, msg: String) -> Self {
+        Self {
+            errors: vec![msg],
+            kind,
+            part: None,
+            len: token_len(i),
+            addr: i.as_ptr() as usize,
+            offset: None,
+        }
+    }
+
+    /// Error of the rule part `part` of the rule starting at `i`, e.g. a missing FREQ
+    pub(crate) fn rule_part_error(i: &str, part: &'static str, e: impl fmt::Display) -> Self {
+        let mut this = Self::new(
+            i,
+            ParseErrorKind::InvalidValue(e.to_string()),
+            format!("failed to parse '{i}' - {e}"),
+        );
+        this.part = Some(part);
+        this
+    }
+
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
+    /// Name of the RRULE part whose value failed to parse, e.g. `BYDAY`
+    pub fn rule_part(&self) -> Option<&'static str> {
+        self.part
     }
+
+    /// Tokens which would have been accepted at the location of the error
+    pub fn expected(&self) -> &[&'static str] {
+        match self.kind {
+            ParseErrorKind::Expected(tokens) => tokens,
+            _ => &[],
+        }
+    }
+
+    /// Byte offset of the error in the input passed to [`ParseError::locate`]
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Byte range of the offending token, which ends at the next `;`, `,` or line break
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.offset.map(|offset| offset..offset + self.len)
+    }
+
+    /// Resolve the offset of the error in `input`, which must be the input the failed parser was
+    /// called with
+    ///
+    /// The `FromStr` implementations locate their errors already.
+    pub fn locate(mut self, input: &str) -> Self {
+        let start = input.as_ptr() as usize;
+
+        if (start..=start + input.len()).contains(&self.addr) {
+            self.offset = Some(self.addr - start);
+        }
+
+        self
+    }
+
+    fn with_kind(mut self, i: &str, kind: ParseErrorKind) -> Self {
+        self.kind = kind;
+        self.len = token_len(i);
+        self.addr = i.as_ptr() as usize;
+        self
+    }
+}
+
+/// Length of the token at the start of `i`, which ends at the next `;`, `,` or line break
+fn token_len(i: &str) -> usize {
+    i.find([';', ',', '\r', '\n']).unwrap_or(i.len())
+}
+
+/// Attribute errors of `parser` to the rule part `name`, which must consume all of its input
+pub(crate) fn rule_part<'a, O>(
+    name: &'static str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    move |i| {
+        let result = match parser(i) {
+            Ok((rem, _)) if !rem.is_empty() => Err(nom::Err::Failure(ParseError::new(
+                rem,
+                ParseErrorKind::Unexpected,
+                format!("unexpected '{rem}'"),
+            ))),
+            result => result,
+        };
+
+        result.map_err(|e| {
+            e.map(|mut e| {
+                e.part.get_or_insert(name);
+                e
+            })
+        })
+    }
+}
+
+/// Replace the kind of recoverable errors of `parser` with `kind`
+pub(crate) fn with_kind<'a, O>(
+    kind: ParseErrorKind,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    move |i| match parser(i) {
+        Err(nom::Err::Error(e)) => Err(nom::Err::Error(e.with_kind(i, kind.clone()))),
+        result => result,
+    }
+}
+
+/// Report recoverable errors of `parser` as expecting one of `tokens`
+pub(crate) fn expected<'a, O>(
+    tokens: &'static [&'static str],
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    with_kind(ParseErrorKind::Expected(tokens), parser)
 }
 
 impl fmt::Debug for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:#?}", self.errors)
+        f.debug_struct("ParseError")
+            .field("errors", &self.errors)
+            .field("kind", &self.kind)
+            .field("part", &self.part)
+            .field("offset", &self.offset)
+            .finish()
     }
 }
 
@@ -34,13 +194,15 @@ impl fmt::Display for ParseError {
     }
 }
 
-impl std::error::Error for ParseError {}
+impl core::error::Error for ParseError {}
 
 impl nom::error::ParseError<&str> for ParseError {
-    fn from_error_kind(i: &str, _kind: nom::error::ErrorKind) -> Self {
-        Self {
-            errors: vec![format!("failed to parse '{i}'")],
-        }
+    fn from_error_kind(i: &str, kind: nom::error::ErrorKind) -> Self {
+        Self::new(
+            i,
+            ParseErrorKind::Other(kind),
+            format!("failed to parse '{i}'"),
+        )
     }
 
     fn append(i: &str, _kind: nom::error::ErrorKind, mut other: Self) -> Self {
@@ -58,11 +220,71 @@ impl nom::error::ContextError<&str> for ParseError {
 
 impl<E> nom::error::FromExternalError<&str, E> for ParseError
 where
-    E: std::error::Error + Send + Sync + 'static,
+    E: core::error::Error + Send + Sync + 'static,
 {
     fn from_external_error(i: &str, _: nom::error::ErrorKind, e: E) -> Self {
-        Self {
-            errors: vec![format!("failed to parse '{i}' - {e}")],
+        Self::new(
+            i,
+            ParseErrorKind::InvalidValue(e.to_string()),
+            format!("failed to parse '{i}' - {e}"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rrule::{RRule, RRuleFromStrError};
+
+    fn parse_error(s: &str) -> ParseError {
+        match s.parse::<RRule>() {
+            Err(RRuleFromStrError::Parse(e)) => e,
+            other => panic!("expected parse error, got {:?}", other),
         }
     }
+
+    #[test]
+    fn expected_tokens() {
+        let s = "DTSTART:20220103T090000\nRRULE:FREQ=WEEKLY;BYDAY=MO,XX;COUNT=2";
+        let e = parse_error(s);
+
+        assert_eq!(e.rule_part(), Some("BYDAY"));
+        assert!(e.expected().contains(&"SU"));
+        assert_eq!(&s[e.span().unwrap()], "XX");
+
+        let e = parse_error("DTSTART:20220103T090000\nRRULE:FREQ=WEEKLY;FOO=1");
+
+        assert_eq!(e.rule_part(), None);
+        assert!(e.expected().contains(&"BYMONTHDAY"));
+        assert_eq!(e.span(), Some(42..47));
+    }
+
+    #[test]
+    fn kinds() {
+        let s = "DTSTART:20220103T090000\nRRULE:FREQ=DAILY;INTERVAL=x";
+        let e = parse_error(s);
+
+        assert_eq!(e.kind(), &ParseErrorKind::InvalidNumber);
+        assert_eq!(e.rule_part(), Some("INTERVAL"));
+        assert_eq!(e.offset(), Some(s.len() - 1));
+
+        let e = parse_error("DTSTART:20220103T090000\nRRULE:FREQ=DAILY;COUNT=2x");
+
+        assert_eq!(e.kind(), &ParseErrorKind::Unexpected);
+        assert_eq!(e.span(), Some(48..49));
+
+        let e = parse_error("DTSTART:20220103T090000\nRRULE:FREQ=DAILY;UNTIL=20220230");
+
+        assert!(matches!(e.kind(), ParseErrorKind::InvalidValue(_)));
+        assert_eq!(e.rule_part(), Some("UNTIL"));
+
+        let e = parse_error("DTSTART:20220103T090000\nRRULE:COUNT=2");
+
+        assert_eq!(
+            e.kind(),
+            &ParseErrorKind::InvalidValue("missing FREQ".into())
+        );
+        assert_eq!(e.rule_part(), Some("FREQ"));
+        assert_eq!(e.offset(), Some(30));
+    }
 }
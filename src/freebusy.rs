@@ -0,0 +1,241 @@
+use crate::dt_prop::local_datetime_with_tz;
+use crate::interval::{EventEnd, Intervals};
+use crate::iter::{RRuleIterYield, RRuleSetIter};
+use crate::rrule_set::RRuleSet;
+use crate::zone::Zone;
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use chrono::NaiveDateTime;
+use core::cmp::Reverse;
+use core::fmt;
+
+/// Period of time from `start` (inclusive) to `end` (exclusive), as in FREEBUSY of
+/// [RFC5545#3.8.2.6]
+///
+/// Times are UTC, as FREEBUSY requires.
+///
+/// [RFC5545#3.8.2.6]: https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.2.6
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Period {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}",
+            self.start.format("%Y%m%dT%H%M%SZ"),
+            self.end.format("%Y%m%dT%H%M%SZ")
+        )
+    }
+}
+
+/// Busy and free periods of a window, busy periods do not overlap or touch each other
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FreeBusy {
+    pub busy: Vec<Period>,
+    pub free: Vec<Period>,
+}
+
+impl FreeBusy {
+    /// Merge the occurrences of all events within the UTC window from `start` to `end`
+    ///
+    /// Floating events are taken in the `floating` timezone. Occurrences overlapping the window
+    /// are clipped to it. The occurrences of all events are merged in order of their start, so
+    /// each event is only expanded up to `end`.
+    pub fn new<'a, Z: Zone + 'a>(
+        events: impl IntoIterator<Item = (&'a RRuleSet<Z>, &'a EventEnd<Z>)>,
+        floating: Z,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Self {
+        let mut streams: Vec<Intervals<RRuleSetIter<Z>>> = events
+            .into_iter()
+            .map(|(set, event_end)| set.intervals(event_end))
+            .collect();
+
+        let utc = |yielded: RRuleIterYield<Z>| match yielded {
+            RRuleIterYield::DateTimeLocal(local) => {
+                local_datetime_with_tz(local, floating).naive_utc()
+            }
+            RRuleIterYield::DateTimeTz(datetime) => datetime.naive_utc(),
+        };
+
+        // Next interval of each stream in UTC, keyed by its start
+        let mut heap = BinaryHeap::new();
+        let next = |i: usize, streams: &mut [Intervals<RRuleSetIter<Z>>]| {
+            streams[i]
+                .next()
+                .map(|(from, to)| (utc(from), utc(to)))
+                .filter(|&(from, _)| from < end)
+        };
+
+        for i in 0..streams.len() {
+            if let Some((from, to)) = next(i, &mut streams) {
+                heap.push(Reverse((from, to, i)));
+            }
+        }
+
+        let mut free_busy = Self::default();
+
+        while let Some(Reverse((from, to, i))) = heap.pop() {
+            if let Some((from, to)) = next(i, &mut streams) {
+                heap.push(Reverse((from, to, i)));
+            }
+
+            let (from, to) = (from.max(start), to.min(end));
+
+            if from >= to {
+                continue;
+            }
+
+            match free_busy.busy.last_mut() {
+                Some(last) if from <= last.end => last.end = last.end.max(to),
+                _ => free_busy.busy.push(Period {
+                    start: from,
+                    end: to,
+                }),
+            }
+        }
+
+        let mut free_from = start;
+
+        for busy in &free_busy.busy {
+            if free_from < busy.start {
+                free_busy.free.push(Period {
+                    start: free_from,
+                    end: busy.start,
+                });
+            }
+
+            free_from = busy.end;
+        }
+
+        if free_from < end {
+            free_busy.free.push(Period {
+                start: free_from,
+                end,
+            });
+        }
+
+        free_busy
+    }
+}
+
+/// Serializes to FREEBUSY properties, one per type with any periods
+impl fmt::Display for FreeBusy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+
+        for (fb_type, periods) in [("BUSY", &self.busy), ("FREE", &self.free)] {
+            if periods.is_empty() {
+                continue;
+            }
+
+            if !first {
+                writeln!(f)?;
+            }
+            first = false;
+
+            write!(f, "FREEBUSY;FBTYPE={}:", fb_type)?;
+
+            for (i, period) in periods.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{}", period)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "chrono-tz")]
+mod tests {
+    use super::*;
+    use chrono_tz::Tz;
+
+    fn datetime(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%S").unwrap()
+    }
+
+    #[test]
+    fn free_busy() {
+        let event = |set: &str, end: &str| -> (RRuleSet, EventEnd) {
+            (set.parse().unwrap(), EventEnd::parse(end).unwrap().1)
+        };
+
+        let events = [
+            event(
+                "DTSTART:20220103T090000Z\nRRULE:FREQ=DAILY",
+                "DURATION:PT1H",
+            ),
+            event(
+                "DTSTART;TZID=Europe/Berlin:20220103T103000\nRRULE:FREQ=DAILY;INTERVAL=2",
+                "DURATION:PT1H",
+            ),
+            event(
+                "DTSTART:20220102T230000Z\nRRULE:FREQ=DAILY;COUNT=1",
+                "DURATION:PT9H30M",
+            ),
+        ];
+
+        let free_busy = FreeBusy::new(
+            events.iter().map(|(set, end)| (set, end)),
+            Tz::UTC,
+            datetime("20220103T000000"),
+            datetime("20220104T000000"),
+        );
+
+        assert_eq!(
+            free_busy.to_string(),
+            "FREEBUSY;FBTYPE=BUSY:20220103T000000Z/20220103T083000Z,\
+             20220103T090000Z/20220103T103000Z\n\
+             FREEBUSY;FBTYPE=FREE:20220103T083000Z/20220103T090000Z,\
+             20220103T103000Z/20220104T000000Z"
+        );
+
+        let free_busy = FreeBusy::new(
+            events.iter().map(|(set, end)| (set, end)),
+            Tz::UTC,
+            datetime("20220105T000000"),
+            datetime("20220106T000000"),
+        );
+
+        assert_eq!(
+            free_busy.busy,
+            [Period {
+                start: datetime("20220105T090000"),
+                end: datetime("20220105T103000"),
+            }]
+        );
+        assert_eq!(free_busy.free.len(), 2);
+    }
+
+    #[test]
+    fn floating() {
+        let set: RRuleSet = "DTSTART:20220103T090000\nRRULE:FREQ=DAILY;COUNT=1"
+            .parse()
+            .unwrap();
+        let end = EventEnd::parse("DURATION:PT1H").unwrap().1;
+
+        let free_busy = FreeBusy::new(
+            [(&set, &end)],
+            Tz::Europe__Berlin,
+            datetime("20220103T000000"),
+            datetime("20220104T000000"),
+        );
+
+        assert_eq!(
+            free_busy.busy,
+            [Period {
+                start: datetime("20220103T080000"),
+                end: datetime("20220103T090000"),
+            }]
+        );
+    }
+}
@@ -1,11 +1,11 @@
-use crate::error::IResult;
+use crate::error::{expected, IResult};
+use core::fmt;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::combinator::map;
 use nom::error::context;
-use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Frequency {
     Secondly,
     Minutely,
@@ -16,21 +16,32 @@ pub enum Frequency {
     Yearly,
 }
 
+const FREQUENCIES: &[&str] = &[
+    "SECONDLY", "MINUTELY", "HOURLY", "DAILY", "WEEKLY", "MONTHLY", "YEARLY",
+];
+
 impl Frequency {
     pub(crate) fn parse(i: &str) -> IResult<&str, Self> {
-        context(
-            "invalid FREQ value",
-            alt((
-                map(tag("SECONDLY"), |_| Self::Secondly),
-                map(tag("MINUTELY"), |_| Self::Minutely),
-                map(tag("HOURLY"), |_| Self::Hourly),
-                map(tag("DAILY"), |_| Self::Daily),
-                map(tag("WEEKLY"), |_| Self::Weekly),
-                map(tag("MONTHLY"), |_| Self::Monthly),
-                map(tag("YEARLY"), |_| Self::Yearly),
-            )),
+        expected(
+            FREQUENCIES,
+            context(
+                "invalid FREQ value",
+                alt((
+                    map(tag("SECONDLY"), |_| Self::Secondly),
+                    map(tag("MINUTELY"), |_| Self::Minutely),
+                    map(tag("HOURLY"), |_| Self::Hourly),
+                    map(tag("DAILY"), |_| Self::Daily),
+                    map(tag("WEEKLY"), |_| Self::Weekly),
+                    map(tag("MONTHLY"), |_| Self::Monthly),
+                    map(tag("YEARLY"), |_| Self::Yearly),
+                )),
+            ),
         )(i)
     }
+
+    pub(crate) fn is_sub_daily(self) -> bool {
+        matches!(self, Self::Secondly | Self::Minutely | Self::Hourly)
+    }
 }
 
 impl fmt::Display for Frequency {
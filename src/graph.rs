@@ -0,0 +1,619 @@
+use crate::byday::ByDay;
+use crate::dt::Dt;
+use crate::dt_prop::{local_datetime_with_tz, DtProperty, DtStart};
+use crate::freq::Frequency;
+use crate::recur::Recur;
+use crate::rrule::RRule;
+use crate::weekday::Weekday;
+use crate::zone::Zone;
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+use chrono::{Datelike, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum GraphParseError {
+    #[error("unknown timezone '{0}'")]
+    UnknownTimezone(String),
+    #[error("range of type {0:?} requires {1}")]
+    MissingRangeValue(RangeType, &'static str),
+    #[error("pattern of type {0:?} requires {1}")]
+    MissingPatternValue(PatternType, &'static str),
+}
+
+/// Parts of a rule that Microsoft Graph cannot express
+#[derive(Debug, thiserror::Error)]
+pub enum GraphUnsupportedError {
+    #[error("FREQ {0} is not supported")]
+    Frequency(Frequency),
+    #[error("{0} is not supported")]
+    RulePart(&'static str),
+    #[error("{0} is not supported with FREQ {1}")]
+    RulePartInFreq(&'static str, Frequency),
+    #[error("only a single {0} value is supported")]
+    MultipleValues(&'static str),
+    #[error("BYDAY offset and BYSETPOS {0} have no corresponding index")]
+    Index(i32),
+    #[error("BYDAY offsets must be equal")]
+    MixedByDay,
+    #[error("negative BYMONTHDAY is not supported")]
+    NegativeMonthDay,
+}
+
+/// `patternedRecurrence` resource
+///
+/// See <https://learn.microsoft.com/en-us/graph/api/resources/patternedrecurrence>
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatternedRecurrence {
+    pub pattern: RecurrencePattern,
+    pub range: RecurrenceRange,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrencePattern {
+    #[serde(rename = "type")]
+    pub pattern_type: PatternType,
+    pub interval: u32,
+    /// 1-12, 0 if unused
+    #[serde(default)]
+    pub month: u32,
+    /// 1-31, 0 if unused
+    #[serde(default)]
+    pub day_of_month: u32,
+    #[serde(default)]
+    pub days_of_week: Vec<DayOfWeek>,
+    #[serde(default = "sunday")]
+    pub first_day_of_week: DayOfWeek,
+    #[serde(default)]
+    pub index: WeekIndex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PatternType {
+    Daily,
+    Weekly,
+    AbsoluteMonthly,
+    RelativeMonthly,
+    AbsoluteYearly,
+    RelativeYearly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DayOfWeek {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+fn sunday() -> DayOfWeek {
+    DayOfWeek::Sunday
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekIndex {
+    #[default]
+    First,
+    Second,
+    Third,
+    Fourth,
+    Last,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrenceRange {
+    #[serde(rename = "type")]
+    pub range_type: RangeType,
+    pub start_date: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub number_of_occurrences: u32,
+    /// IANA timezone name, `None` if the rule is floating
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence_time_zone: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RangeType {
+    EndDate,
+    NoEnd,
+    Numbered,
+}
+
+impl From<DayOfWeek> for Weekday {
+    fn from(day: DayOfWeek) -> Self {
+        match day {
+            DayOfWeek::Monday => Weekday::Monday,
+            DayOfWeek::Tuesday => Weekday::Tuesday,
+            DayOfWeek::Wednesday => Weekday::Wednesday,
+            DayOfWeek::Thursday => Weekday::Thursday,
+            DayOfWeek::Friday => Weekday::Friday,
+            DayOfWeek::Saturday => Weekday::Saturday,
+            DayOfWeek::Sunday => Weekday::Sunday,
+        }
+    }
+}
+
+impl From<Weekday> for DayOfWeek {
+    fn from(day: Weekday) -> Self {
+        match day {
+            Weekday::Monday => DayOfWeek::Monday,
+            Weekday::Tuesday => DayOfWeek::Tuesday,
+            Weekday::Wednesday => DayOfWeek::Wednesday,
+            Weekday::Thursday => DayOfWeek::Thursday,
+            Weekday::Friday => DayOfWeek::Friday,
+            Weekday::Saturday => DayOfWeek::Saturday,
+            Weekday::Sunday => DayOfWeek::Sunday,
+        }
+    }
+}
+
+impl WeekIndex {
+    fn nth(self) -> i32 {
+        match self {
+            WeekIndex::First => 1,
+            WeekIndex::Second => 2,
+            WeekIndex::Third => 3,
+            WeekIndex::Fourth => 4,
+            WeekIndex::Last => -1,
+        }
+    }
+
+    fn from_nth(nth: i32) -> Result<Self, GraphUnsupportedError> {
+        match nth {
+            1 => Ok(WeekIndex::First),
+            2 => Ok(WeekIndex::Second),
+            3 => Ok(WeekIndex::Third),
+            4 => Ok(WeekIndex::Fourth),
+            -1 => Ok(WeekIndex::Last),
+            nth => Err(GraphUnsupportedError::Index(nth)),
+        }
+    }
+}
+
+impl PatternedRecurrence {
+    /// Convert to an RRule, starting at `time` on the range's start date
+    ///
+    /// Graph keeps the time of day on the event, without it the rule's DTSTART is a DATE.
+    pub fn to_rrule<Z: Zone>(&self, time: Option<NaiveTime>) -> Result<RRule<Z>, GraphParseError> {
+        let pattern = &self.pattern;
+        let range = &self.range;
+
+        let tz = match &range.recurrence_time_zone {
+            Some(tz) => {
+                Some(Z::from_tzid(tz).ok_or_else(|| GraphParseError::UnknownTimezone(tz.clone()))?)
+            }
+            None => None,
+        };
+
+        let dt_start = DtProperty {
+            dt: match time {
+                Some(time) => Dt::DateTimeLocal(range.start_date.and_time(time)),
+                None => Dt::Date(range.start_date),
+            },
+            tz: if time.is_some() { tz } else { None },
+        };
+
+        let freq = match pattern.pattern_type {
+            PatternType::Daily => Frequency::Daily,
+            PatternType::Weekly => Frequency::Weekly,
+            PatternType::AbsoluteMonthly | PatternType::RelativeMonthly => Frequency::Monthly,
+            PatternType::AbsoluteYearly | PatternType::RelativeYearly => Frequency::Yearly,
+        };
+
+        let mut recur = Recur::new(freq);
+
+        // Only relevant for weekly patterns, but kept on all to allow converting back losslessly
+        recur.week_start = Some(pattern.first_day_of_week.into());
+
+        if pattern.interval > 1 {
+            recur.interval = Some(pattern.interval);
+        }
+
+        let days: Vec<Weekday> = pattern.days_of_week.iter().map(|&d| d.into()).collect();
+        let missing = |what| GraphParseError::MissingPatternValue(pattern.pattern_type, what);
+
+        match pattern.pattern_type {
+            PatternType::Daily => {}
+            PatternType::Weekly => {
+                if days.is_empty() {
+                    return Err(missing("daysOfWeek"));
+                }
+
+                recur.by_day = days.into_iter().map(ByDay::All).collect();
+            }
+            PatternType::AbsoluteMonthly | PatternType::AbsoluteYearly => {
+                if pattern.day_of_month == 0 {
+                    return Err(missing("dayOfMonth"));
+                }
+
+                recur.by_month_day = vec![pattern.day_of_month as i32];
+            }
+            PatternType::RelativeMonthly | PatternType::RelativeYearly => {
+                let nth = pattern.index.nth();
+
+                // The index selects among all given days, e.g. the last weekday of the month
+                match days.as_slice() {
+                    [] => return Err(missing("daysOfWeek")),
+                    [day] => recur.by_day = vec![ByDay::Nth(*day, nth)],
+                    days => {
+                        recur.by_day = days.iter().copied().map(ByDay::All).collect();
+                        recur.by_set_pos = vec![nth];
+                    }
+                }
+            }
+        }
+
+        if freq == Frequency::Yearly {
+            if pattern.month == 0 {
+                return Err(missing("month"));
+            }
+
+            recur.by_month = vec![pattern.month];
+        }
+
+        match range.range_type {
+            RangeType::NoEnd => {}
+            RangeType::Numbered => recur.count = Some(range.number_of_occurrences),
+            RangeType::EndDate => {
+                let end_date = range.end_date.ok_or(GraphParseError::MissingRangeValue(
+                    RangeType::EndDate,
+                    "endDate",
+                ))?;
+
+                // The end date is inclusive
+                let end = end_date.and_hms_opt(23, 59, 59).expect("valid time");
+
+                recur.until = Some(match (time, dt_start.tz) {
+                    (None, _) => Dt::Date(end_date),
+                    (Some(_), None) => Dt::DateTimeLocal(end),
+                    (Some(_), Some(tz)) => {
+                        Dt::DateTimeUtc(local_datetime_with_tz(end, tz).with_timezone(&Utc))
+                    }
+                });
+            }
+        }
+
+        Ok(RRule::new(DtStart(dt_start), recur))
+    }
+}
+
+impl<Z: Zone> RRule<Z> {
+    /// Convert to a Graph `patternedRecurrence`
+    ///
+    /// The time of day of DTSTART is not part of the recurrence in Graph and must be set on the event.
+    pub fn to_graph(&self) -> Result<PatternedRecurrence, GraphUnsupportedError> {
+        let recur = &self.recur;
+        let freq = recur.freq;
+
+        if freq.is_sub_daily() {
+            return Err(GraphUnsupportedError::Frequency(freq));
+        }
+
+        for (name, is_empty) in [
+            ("BYSECOND", recur.by_second.is_empty()),
+            ("BYMINUTE", recur.by_minute.is_empty()),
+            ("BYHOUR", recur.by_hour.is_empty()),
+            ("BYYEARDAY", recur.by_year_day.is_empty()),
+            ("BYWEEKNO", recur.by_week_no.is_empty()),
+        ] {
+            if !is_empty {
+                return Err(GraphUnsupportedError::RulePart(name));
+            }
+        }
+
+        let dt_start = self.dt_start.0.to_datetime().naive_local();
+
+        let mut pattern = RecurrencePattern {
+            pattern_type: PatternType::Daily,
+            interval: recur.interval.unwrap_or(1),
+            month: 0,
+            day_of_month: 0,
+            days_of_week: vec![],
+            first_day_of_week: DayOfWeek::from(recur.week_start.unwrap_or(Weekday::Monday)),
+            index: WeekIndex::First,
+        };
+
+        let not_in_freq = |name| GraphUnsupportedError::RulePartInFreq(name, freq);
+
+        match freq {
+            Frequency::Daily | Frequency::Weekly => {
+                if !recur.by_month.is_empty() {
+                    return Err(not_in_freq("BYMONTH"));
+                }
+
+                if !recur.by_month_day.is_empty() {
+                    return Err(not_in_freq("BYMONTHDAY"));
+                }
+
+                if !recur.by_set_pos.is_empty() {
+                    return Err(not_in_freq("BYSETPOS"));
+                }
+
+                if freq == Frequency::Daily {
+                    if !recur.by_day.is_empty() {
+                        return Err(not_in_freq("BYDAY"));
+                    }
+                } else {
+                    pattern.pattern_type = PatternType::Weekly;
+                    pattern.days_of_week = if recur.by_day.is_empty() {
+                        vec![weekday_of(dt_start.date()).into()]
+                    } else {
+                        recur
+                            .by_day
+                            .iter()
+                            .map(|by_day| match by_day {
+                                ByDay::All(day) => Ok(DayOfWeek::from(*day)),
+                                ByDay::Nth(..) => Err(not_in_freq("BYDAY offset")),
+                            })
+                            .collect::<Result<_, _>>()?
+                    };
+                }
+            }
+            Frequency::Monthly | Frequency::Yearly => {
+                let relative = !recur.by_day.is_empty();
+
+                if relative {
+                    if !recur.by_month_day.is_empty() {
+                        return Err(not_in_freq("BYMONTHDAY with BYDAY"));
+                    }
+
+                    let (days, nth) = relative_days(&recur.by_day, &recur.by_set_pos)?;
+
+                    pattern.days_of_week = days;
+                    pattern.index = WeekIndex::from_nth(nth)?;
+                } else {
+                    if !recur.by_set_pos.is_empty() {
+                        return Err(not_in_freq("BYSETPOS without BYDAY"));
+                    }
+
+                    pattern.day_of_month = match recur.by_month_day.as_slice() {
+                        [] => dt_start.day(),
+                        [day] if *day > 0 => *day as u32,
+                        [_] => return Err(GraphUnsupportedError::NegativeMonthDay),
+                        _ => return Err(GraphUnsupportedError::MultipleValues("BYMONTHDAY")),
+                    };
+                }
+
+                if freq == Frequency::Monthly {
+                    if !recur.by_month.is_empty() {
+                        return Err(not_in_freq("BYMONTH"));
+                    }
+
+                    pattern.pattern_type = if relative {
+                        PatternType::RelativeMonthly
+                    } else {
+                        PatternType::AbsoluteMonthly
+                    };
+                } else {
+                    pattern.month = match recur.by_month.as_slice() {
+                        // BYDAY and BYMONTHDAY expand to every month of the year without BYMONTH
+                        [] if relative => return Err(not_in_freq("BYDAY without BYMONTH")),
+                        [] if !recur.by_month_day.is_empty() => {
+                            return Err(not_in_freq("BYMONTHDAY without BYMONTH"))
+                        }
+                        [] => dt_start.month(),
+                        [month] => *month,
+                        _ => return Err(GraphUnsupportedError::MultipleValues("BYMONTH")),
+                    };
+
+                    pattern.pattern_type = if relative {
+                        PatternType::RelativeYearly
+                    } else {
+                        PatternType::AbsoluteYearly
+                    };
+                }
+            }
+            _ => unreachable!("sub-daily frequencies are rejected above"),
+        }
+
+        // Graph has no notion of UTC date-times, a zone is needed to not end up floating
+        let recurrence_time_zone = match self.dt_start.0 {
+            DtProperty { tz: Some(tz), .. } => Some(tz.tzid()),
+            DtProperty {
+                dt: Dt::DateTimeUtc(_),
+                ..
+            } => Some("UTC".into()),
+            _ => None,
+        };
+
+        let mut range = RecurrenceRange {
+            range_type: RangeType::NoEnd,
+            start_date: dt_start.date(),
+            end_date: None,
+            number_of_occurrences: 0,
+            recurrence_time_zone,
+        };
+
+        if let Some(count) = recur.count {
+            range.range_type = RangeType::Numbered;
+            range.number_of_occurrences = count;
+        } else if let Some(until) = recur.until {
+            let tz = self.dt_start.0.tz.unwrap_or_else(Z::utc);
+
+            range.range_type = RangeType::EndDate;
+            range.end_date = Some(match until {
+                Dt::Date(date) => date,
+                Dt::DateTimeLocal(datetime) => datetime.date(),
+                Dt::DateTimeUtc(datetime) => datetime.with_timezone(&tz).date_naive(),
+            });
+        }
+
+        Ok(PatternedRecurrence { pattern, range })
+    }
+}
+
+fn weekday_of(date: NaiveDate) -> Weekday {
+    match date.weekday() {
+        chrono::Weekday::Mon => Weekday::Monday,
+        chrono::Weekday::Tue => Weekday::Tuesday,
+        chrono::Weekday::Wed => Weekday::Wednesday,
+        chrono::Weekday::Thu => Weekday::Thursday,
+        chrono::Weekday::Fri => Weekday::Friday,
+        chrono::Weekday::Sat => Weekday::Saturday,
+        chrono::Weekday::Sun => Weekday::Sunday,
+    }
+}
+
+/// Days and index of a relative pattern, either `BYDAY=-1FR` or `BYDAY=MO,TU;BYSETPOS=-1`
+fn relative_days(
+    by_day: &[ByDay],
+    by_set_pos: &[i32],
+) -> Result<(Vec<DayOfWeek>, i32), GraphUnsupportedError> {
+    let mut days = vec![];
+    let mut nths = vec![];
+
+    for by_day in by_day {
+        match *by_day {
+            ByDay::All(day) => days.push(day.into()),
+            ByDay::Nth(day, nth) => {
+                days.push(day.into());
+                nths.push(nth);
+            }
+        }
+    }
+
+    match (nths.as_slice(), by_set_pos) {
+        ([], [nth]) => Ok((days, *nth)),
+        ([], []) => Err(GraphUnsupportedError::RulePart(
+            "BYDAY without offset or BYSETPOS",
+        )),
+        ([], _) => Err(GraphUnsupportedError::MultipleValues("BYSETPOS")),
+        ([nth], []) if by_day.len() == 1 => Ok((days, *nth)),
+        (nths, []) if nths.len() == by_day.len() => {
+            if nths.iter().all(|nth| *nth == nths[0]) {
+                Err(GraphUnsupportedError::MultipleValues("BYDAY with offset"))
+            } else {
+                Err(GraphUnsupportedError::MixedByDay)
+            }
+        }
+        (_, []) => Err(GraphUnsupportedError::MixedByDay),
+        (_, _) => Err(GraphUnsupportedError::RulePart(
+            "BYDAY offset with BYSETPOS",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn to_rrule() {
+        let recurrence: PatternedRecurrence = serde_json::from_str(
+            r#"{
+                "pattern": {
+                    "type": "relativeMonthly",
+                    "interval": 1,
+                    "month": 0,
+                    "dayOfMonth": 0,
+                    "daysOfWeek": ["monday", "tuesday", "wednesday", "thursday", "friday"],
+                    "firstDayOfWeek": "sunday",
+                    "index": "last"
+                },
+                "range": {
+                    "type": "endDate",
+                    "startDate": "2022-01-31",
+                    "endDate": "2022-12-31",
+                    "recurrenceTimeZone": "Europe/Berlin",
+                    "numberOfOccurrences": 0
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let time = NaiveTime::from_hms_opt(17, 0, 0);
+        let rrule: RRule = recurrence.to_rrule(time).unwrap();
+
+        assert_eq!(
+            format!("DTSTART{}\nRRULE:{}", rrule.dt_start.0, rrule.recur),
+            "DTSTART;TZID=Europe/Berlin:20220131T170000\n\
+            RRULE:FREQ=MONTHLY;UNTIL=20221231T225959Z;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1;WKST=SU"
+        );
+
+        assert_eq!(rrule.to_graph().unwrap().pattern, recurrence.pattern);
+        assert_eq!(rrule.to_graph().unwrap().range, recurrence.range);
+    }
+
+    #[test]
+    fn to_graph() {
+        let to_graph = |s: &str| s.parse::<RRule>().unwrap().to_graph();
+
+        let recurrence =
+            to_graph("DTSTART;VALUE=DATE:20220103\nRRULE:FREQ=YEARLY;BYMONTH=1;BYDAY=2MO;COUNT=3")
+                .unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&recurrence).unwrap(),
+            r#"{"pattern":{"type":"relativeYearly","interval":1,"month":1,"dayOfMonth":0,"daysOfWeek":["monday"],"firstDayOfWeek":"monday","index":"second"},"range":{"type":"numbered","startDate":"2022-01-03","numberOfOccurrences":3}}"#
+        );
+
+        let recurrence = to_graph("DTSTART:20220103T090000\nRRULE:FREQ=YEARLY").unwrap();
+
+        assert_eq!(recurrence.pattern.pattern_type, PatternType::AbsoluteYearly);
+        assert_eq!(
+            (recurrence.pattern.month, recurrence.pattern.day_of_month),
+            (1, 3)
+        );
+
+        assert!(matches!(
+            to_graph("DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYDAY=-1FR"),
+            Err(GraphUnsupportedError::RulePartInFreq(
+                "BYDAY without BYMONTH",
+                Frequency::Yearly
+            ))
+        ));
+        assert!(matches!(
+            to_graph("DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYMONTHDAY=1"),
+            Err(GraphUnsupportedError::RulePartInFreq(
+                "BYMONTHDAY without BYMONTH",
+                Frequency::Yearly
+            ))
+        ));
+        assert!(matches!(
+            to_graph("DTSTART:20220103T090000\nRRULE:FREQ=DAILY;BYHOUR=9,12"),
+            Err(GraphUnsupportedError::RulePart("BYHOUR"))
+        ));
+        assert!(matches!(
+            to_graph("DTSTART:20220103T090000\nRRULE:FREQ=MONTHLY;BYDAY=MO;BYSETPOS=1,2"),
+            Err(GraphUnsupportedError::MultipleValues("BYSETPOS"))
+        ));
+        assert!(matches!(
+            to_graph("DTSTART:20220103T090000\nRRULE:FREQ=MONTHLY;BYDAY=-2FR"),
+            Err(GraphUnsupportedError::Index(-2))
+        ));
+    }
+
+    #[test]
+    fn utc_round_trip() {
+        let rrule: RRule = "DTSTART:20220103T090000Z\nRRULE:FREQ=WEEKLY;BYDAY=MO;COUNT=3"
+            .parse()
+            .unwrap();
+        let recurrence = rrule.to_graph().unwrap();
+
+        assert_eq!(
+            recurrence.range.recurrence_time_zone.as_deref(),
+            Some("UTC")
+        );
+
+        let time = NaiveTime::from_hms_opt(9, 0, 0);
+        let round_trip: RRule = recurrence.to_rrule(time).unwrap();
+
+        assert_eq!(
+            round_trip.dt_start.0.to_datetime(),
+            rrule.dt_start.0.to_datetime()
+        );
+        assert_eq!(round_trip.to_graph().unwrap().range, recurrence.range);
+    }
+}
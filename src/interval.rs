@@ -0,0 +1,178 @@
+use crate::dt::Dt;
+use crate::dt_prop::{DtEnd, DtStart};
+use crate::duration::Duration;
+use crate::error::IResult;
+use crate::iter::{RRuleIter, RRuleIterYield, RRuleSetIter};
+use crate::rrule::RRule;
+use crate::rrule_set::RRuleSet;
+use crate::zone::{DefaultZone, Zone};
+use core::fmt;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::combinator::map;
+use nom::sequence::preceded;
+
+/// End of the occurrences of an event, given by DTEND or DURATION
+///
+/// See [RFC5545#3.8.5.3], the length of each occurrence is that of the first one.
+///
+/// [RFC5545#3.8.5.3]: https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.3
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventEnd<Z: Zone = DefaultZone> {
+    DtEnd(DtEnd<Z>),
+    Duration(Duration),
+}
+
+impl<Z: Zone> EventEnd<Z> {
+    pub fn parse(i: &str) -> IResult<&str, Self> {
+        alt((
+            map(DtEnd::parse, Self::DtEnd),
+            map(preceded(tag("DURATION:"), Duration::parse), Self::Duration),
+        ))(i)
+    }
+
+    /// Length of the occurrences of an event starting at `dt_start`
+    pub(crate) fn length(&self, dt_start: &DtStart<Z>) -> Length {
+        match *self {
+            Self::Duration(duration) => Length::Nominal(duration),
+            // All day events last whole days, which are nominal
+            Self::DtEnd(DtEnd(dt_end)) => match (dt_start.0.dt, dt_end.dt) {
+                (Dt::Date(start), Dt::Date(end)) => {
+                    let days = (end - start).num_days();
+
+                    Length::Nominal(Duration {
+                        negative: days < 0,
+                        days: days.unsigned_abs().try_into().unwrap_or(u32::MAX),
+                        ..Duration::default()
+                    })
+                }
+                _ => Length::Exact(dt_end.to_datetime() - dt_start.0.to_datetime()),
+            },
+        }
+    }
+}
+
+impl<Z: Zone> fmt::Display for EventEnd<Z> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DtEnd(dt_end) => write!(f, "DTEND{}", dt_end.0),
+            Self::Duration(duration) => write!(f, "DURATION:{}", duration),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Length {
+    Nominal(Duration),
+    Exact(chrono::Duration),
+}
+
+impl Length {
+    /// End of an occurrence starting at `start`, `None` if out of range
+    pub(crate) fn end<Z: Zone>(&self, start: RRuleIterYield<Z>) -> Option<RRuleIterYield<Z>> {
+        match *self {
+            Self::Nominal(duration) => duration.add_to(start),
+            Self::Exact(duration) => match start {
+                RRuleIterYield::DateTimeLocal(datetime) => datetime
+                    .checked_add_signed(duration)
+                    .map(RRuleIterYield::DateTimeLocal),
+                RRuleIterYield::DateTimeTz(datetime) => datetime
+                    .checked_add_signed(duration)
+                    .map(RRuleIterYield::DateTimeTz),
+            },
+        }
+    }
+}
+
+/// Iterator over the `(start, end)` intervals of occurrences
+///
+/// Ends at the first occurrence whose end is out of range.
+#[derive(Debug)]
+pub struct Intervals<I> {
+    iter: I,
+    length: Length,
+}
+
+impl<Z: Zone, I: Iterator<Item = RRuleIterYield<Z>>> Iterator for Intervals<I> {
+    type Item = (RRuleIterYield<Z>, RRuleIterYield<Z>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.iter.next()?;
+        let end = self.length.end(start)?;
+
+        Some((start, end))
+    }
+}
+
+impl<Z: Zone> RRule<Z> {
+    /// Intervals of the occurrences of an event ending as given by `end`
+    pub fn intervals(&self, end: &EventEnd<Z>) -> Intervals<RRuleIter<Z>> {
+        Intervals {
+            iter: RRuleIter::new(self),
+            length: end.length(&self.dt_start),
+        }
+    }
+}
+
+impl<Z: Zone> RRuleSet<Z> {
+    /// Intervals of the occurrences of an event ending as given by `end`
+    pub fn intervals(&self, end: &EventEnd<Z>) -> Intervals<RRuleSetIter<Z>> {
+        Intervals {
+            iter: self.iter(),
+            length: end.length(&self.dt_start),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    fn intervals(set: &str, end: &str) -> Vec<String> {
+        let set: RRuleSet = set.parse().unwrap();
+        let (_, end) = EventEnd::parse(end).unwrap();
+
+        set.intervals(&end)
+            .map(|(start, end)| {
+                format!(
+                    "{}/{}",
+                    start.local().format("%d %H:%M"),
+                    end.local().format("%d %H:%M")
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn across_dst() {
+        // Clocks in Berlin go forward on the 27th of March 2022
+        let set = "DTSTART;TZID=Europe/Berlin:20220326T090000\nRRULE:FREQ=DAILY;COUNT=2";
+
+        assert_eq!(
+            intervals(set, "DURATION:P1D"),
+            ["26 09:00/27 09:00", "27 09:00/28 09:00"]
+        );
+        assert_eq!(
+            intervals(set, "DURATION:PT24H"),
+            ["26 09:00/27 10:00", "27 09:00/28 09:00"]
+        );
+        assert_eq!(
+            intervals(set, "DTEND;TZID=Europe/Berlin:20220327T100000"),
+            ["26 09:00/27 10:00", "27 09:00/28 09:00"]
+        );
+    }
+
+    #[test]
+    fn all_day() {
+        let set = "DTSTART;VALUE=DATE:20220326\nRRULE:FREQ=WEEKLY;COUNT=2";
+
+        assert_eq!(
+            intervals(set, "DTEND;VALUE=DATE:20220328"),
+            ["26 00:00/28 00:00", "02 00:00/04 00:00"]
+        );
+    }
+}
@@ -0,0 +1,642 @@
+use crate::dt::{Dt, DtParseError};
+use crate::dt_prop::{local_datetime_with_tz, DtProperty, DtStart};
+use crate::error::{IResult, ParseError};
+use crate::freq::Frequency;
+use crate::iter::RRuleIterYield;
+use crate::recur::Recur;
+use crate::rrule::RRule;
+use crate::util::parse_u32;
+use crate::zone::{DefaultZone, Zone};
+use alloc::string::{String, ToString};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Months, NaiveDate, NaiveTime, TimeZone, Utc,
+};
+use core::fmt;
+use core::str::FromStr;
+use nom::branch::alt;
+use nom::bytes::complete::take_while_m_n;
+use nom::character::complete::{char, one_of};
+use nom::combinator::{all_consuming, map, map_res, opt, recognize};
+use nom::sequence::{pair, preceded, terminated, tuple};
+use nom::Finish;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IsoParseError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("duration must have at least one component")]
+    EmptyDuration,
+    #[error("end must not be before start")]
+    EndBeforeStart,
+    #[error("{0} is not supported")]
+    Unsupported(&'static str),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IsoFormatError {
+    #[error("{0} cannot be expressed as ISO 8601 repeating interval")]
+    Unsupported(&'static str),
+}
+
+/// ISO 8601 duration, e.g. `P1Y2M10DT2H30M`
+///
+/// Years, months, weeks and days are nominal and follow the calendar,
+/// hours, minutes and seconds are exact.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IsoDuration {
+    pub years: u32,
+    pub months: u32,
+    pub weeks: u32,
+    pub days: u32,
+    pub hours: u32,
+    pub minutes: u32,
+    pub seconds: u32,
+}
+
+impl IsoDuration {
+    fn parse(i: &str) -> IResult<&str, Self> {
+        let component = |designator| opt(terminated(parse_u32, char(designator)));
+
+        map(
+            preceded(
+                char('P'),
+                tuple((
+                    component('Y'),
+                    component('M'),
+                    component('W'),
+                    component('D'),
+                    opt(preceded(
+                        char('T'),
+                        tuple((component('H'), component('M'), component('S'))),
+                    )),
+                )),
+            ),
+            |(years, months, weeks, days, time)| {
+                let (hours, minutes, seconds) = time.unwrap_or_default();
+
+                Self {
+                    years: years.unwrap_or_default(),
+                    months: months.unwrap_or_default(),
+                    weeks: weeks.unwrap_or_default(),
+                    days: days.unwrap_or_default(),
+                    hours: hours.unwrap_or_default(),
+                    minutes: minutes.unwrap_or_default(),
+                    seconds: seconds.unwrap_or_default(),
+                }
+            },
+        )(i)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Express the duration as a single FREQ and INTERVAL if its components can be merged exactly
+    ///
+    /// Months and days can't be merged as months differ in length.
+    fn as_frequency(&self) -> Option<(Frequency, u32)> {
+        let months = self.years * 12 + self.months;
+        let seconds = (self.weeks * 7 + self.days) * 86400
+            + self.hours * 3600
+            + self.minutes * 60
+            + self.seconds;
+
+        match (months, seconds) {
+            (0, 0) => None,
+            (0, seconds) => Some(seconds_as_frequency(seconds)),
+            (months, 0) if months.is_multiple_of(12) => Some((Frequency::Yearly, months / 12)),
+            (months, 0) => Some((Frequency::Monthly, months)),
+            _ => None,
+        }
+    }
+
+    /// Add the duration `n` times to `start`
+    fn add_to(&self, start: DateTime<DefaultZone>, n: u32) -> Option<DateTime<DefaultZone>> {
+        let months = Months::new((self.years * 12 + self.months).checked_mul(n)?);
+        let days = i64::from(self.weeks * 7 + self.days) * i64::from(n);
+        let seconds =
+            (i64::from(self.hours) * 3600 + i64::from(self.minutes) * 60 + i64::from(self.seconds))
+                * i64::from(n);
+
+        let local = start
+            .naive_local()
+            .checked_add_months(months)?
+            .checked_add_signed(Duration::days(days))?;
+
+        let datetime = local_datetime_with_tz(local, start.timezone());
+
+        datetime.checked_add_signed(Duration::seconds(seconds))
+    }
+}
+
+/// Whether adding months to `start` may hit a day that doesn't exist
+///
+/// ISO 8601 clamps such days to the end of the month, while RRULE skips them.
+fn may_clamp(freq: Frequency, start: &Dt) -> bool {
+    let day = match start {
+        Dt::Date(date) => date.day(),
+        Dt::DateTimeLocal(datetime) => datetime.day(),
+        Dt::DateTimeUtc(datetime) => datetime.day(),
+    };
+
+    matches!(freq, Frequency::Monthly | Frequency::Yearly) && day > 28
+}
+
+fn seconds_as_frequency(seconds: u32) -> (Frequency, u32) {
+    if seconds.is_multiple_of(604800) {
+        (Frequency::Weekly, seconds / 604800)
+    } else if seconds.is_multiple_of(86400) {
+        (Frequency::Daily, seconds / 86400)
+    } else if seconds.is_multiple_of(3600) {
+        (Frequency::Hourly, seconds / 3600)
+    } else if seconds.is_multiple_of(60) {
+        (Frequency::Minutely, seconds / 60)
+    } else {
+        (Frequency::Secondly, seconds)
+    }
+}
+
+impl FromStr for IsoDuration {
+    type Err = IsoParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (_, duration) = all_consuming(Self::parse)(s).finish()?;
+
+        if duration.is_zero() {
+            return Err(IsoParseError::EmptyDuration);
+        }
+
+        Ok(duration)
+    }
+}
+
+impl fmt::Display for IsoDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "P")?;
+
+        for (value, designator) in [
+            (self.years, 'Y'),
+            (self.months, 'M'),
+            (self.weeks, 'W'),
+            (self.days, 'D'),
+        ] {
+            if value > 0 {
+                write!(f, "{}{}", value, designator)?;
+            }
+        }
+
+        if self.hours > 0 || self.minutes > 0 || self.seconds > 0 {
+            write!(f, "T")?;
+
+            for (value, designator) in [(self.hours, 'H'), (self.minutes, 'M'), (self.seconds, 'S')]
+            {
+                if value > 0 {
+                    write!(f, "{}{}", value, designator)?;
+                }
+            }
+        } else if self.is_zero() {
+            write!(f, "T0S")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// ISO 8601 repeating interval, e.g. `R5/2008-03-01T13:00:00Z/P1Y2M10DT2H30M`
+///
+/// Only the `start/duration` and `start/end` forms are supported.
+#[derive(Debug, Clone, Copy)]
+pub struct RepeatingInterval {
+    /// Number of repetitions, `None` if unbounded
+    pub repetitions: Option<u32>,
+    pub start: Dt,
+    pub duration: IsoDuration,
+}
+
+fn number(digits: usize) -> impl Fn(&str) -> IResult<&str, u32> {
+    move |i| {
+        map_res(
+            take_while_m_n(digits, digits, |c: char| c.is_ascii_digit()),
+            u32::from_str,
+        )(i)
+    }
+}
+
+/// `YYYY-MM-DD` or `YYYYMMDD`
+fn parse_date(i: &str) -> IResult<&str, NaiveDate> {
+    map_res(
+        alt((
+            tuple((
+                number(4),
+                preceded(char('-'), number(2)),
+                preceded(char('-'), number(2)),
+            )),
+            tuple((number(4), number(2), number(2))),
+        )),
+        |(year, month, day)| {
+            NaiveDate::from_ymd_opt(year as i32, month, day).ok_or(DtParseError::InvalidDate)
+        },
+    )(i)
+}
+
+/// `HH:MM[:SS][.fff]` or `HHMM[SS]`, fractions of seconds are truncated
+fn parse_time(i: &str) -> IResult<&str, NaiveTime> {
+    map_res(
+        terminated(
+            alt((
+                tuple((
+                    number(2),
+                    preceded(char(':'), number(2)),
+                    opt(preceded(char(':'), number(2))),
+                )),
+                tuple((number(2), number(2), opt(number(2)))),
+            )),
+            opt(pair(
+                one_of(".,"),
+                take_while_m_n(1, 9, |c: char| c.is_ascii_digit()),
+            )),
+        ),
+        |(hour, minute, second)| {
+            NaiveTime::from_hms_opt(hour, minute, second.unwrap_or_default())
+                .ok_or(DtParseError::InvalidTime)
+        },
+    )(i)
+}
+
+/// `Z`, `±HH`, `±HH:MM` or `±HHMM`
+fn parse_offset(i: &str) -> IResult<&str, FixedOffset> {
+    alt((
+        map(char('Z'), |_| {
+            FixedOffset::east_opt(0).expect("valid offset")
+        }),
+        map_res(
+            tuple((
+                one_of("+-"),
+                number(2),
+                opt(preceded(opt(char(':')), number(2))),
+            )),
+            |(sign, hours, minutes)| {
+                let seconds = (hours * 3600 + minutes.unwrap_or_default() * 60) as i32;
+                let seconds = if sign == '-' { -seconds } else { seconds };
+
+                FixedOffset::east_opt(seconds).ok_or(IsoParseError::Unsupported("invalid offset"))
+            },
+        ),
+    ))(i)
+}
+
+/// Date or datetime, datetimes with offset are converted to UTC
+fn parse_dt(i: &str) -> IResult<&str, Dt> {
+    map(
+        pair(
+            parse_date,
+            opt(preceded(char('T'), pair(parse_time, opt(parse_offset)))),
+        ),
+        |(date, time)| match time {
+            None => Dt::Date(date),
+            Some((time, None)) => Dt::DateTimeLocal(date.and_time(time)),
+            Some((time, Some(offset))) => {
+                let utc = date.and_time(time) - Duration::seconds(offset.local_minus_utc().into());
+
+                Dt::DateTimeUtc(Utc.from_utc_datetime(&utc))
+            }
+        },
+    )(i)
+}
+
+enum Second {
+    Duration(IsoDuration),
+    End(Dt),
+}
+
+impl RepeatingInterval {
+    fn parse(i: &str) -> IResult<&str, (Option<u32>, Dt, Second)> {
+        tuple((
+            preceded(
+                char('R'),
+                alt((
+                    map(recognize(pair(char('-'), char('1'))), |_| None),
+                    opt(parse_u32),
+                )),
+            ),
+            preceded(char('/'), parse_dt),
+            preceded(
+                char('/'),
+                alt((
+                    map(IsoDuration::parse, Second::Duration),
+                    map(parse_dt, Second::End),
+                )),
+            ),
+        ))(i)
+    }
+
+    /// Convert to an RRule if the duration maps to a single FREQ and INTERVAL
+    pub fn to_rrule(&self) -> Option<RRule> {
+        let (freq, interval) = self.duration.as_frequency()?;
+
+        if self.start.is_date() && freq.is_sub_daily() || may_clamp(freq, &self.start) {
+            return None;
+        }
+
+        let mut recur = Recur::new(freq);
+        recur.count = self.repetitions;
+
+        if interval > 1 {
+            recur.interval = Some(interval);
+        }
+
+        let dt_start = DtStart(DtProperty {
+            dt: self.start,
+            tz: None,
+        });
+
+        Some(RRule::new(dt_start, recur))
+    }
+
+    /// Iterate the start of each interval, adding the duration to the start as often as needed
+    pub fn iter(&self) -> RepeatingIntervalIter {
+        let dt_start = DtProperty {
+            dt: self.start,
+            tz: None,
+        };
+
+        RepeatingIntervalIter {
+            start: dt_start.to_datetime(),
+            tz: dt_start.result_tz(),
+            duration: self.duration,
+            remaining: self.repetitions,
+            n: 0,
+        }
+    }
+}
+
+impl FromStr for RepeatingInterval {
+    type Err = IsoParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (_, (repetitions, start, second)) = all_consuming(Self::parse)(s).finish()?;
+
+        let duration = match second {
+            Second::Duration(duration) => duration,
+            Second::End(end) => match (start, end) {
+                (Dt::Date(start), Dt::Date(end)) => IsoDuration {
+                    days: u32::try_from((end - start).num_days())
+                        .map_err(|_| IsoParseError::EndBeforeStart)?,
+                    ..Default::default()
+                },
+                (Dt::DateTimeLocal(start), Dt::DateTimeLocal(end)) => exact_duration(end - start)?,
+                (Dt::DateTimeUtc(start), Dt::DateTimeUtc(end)) => exact_duration(end - start)?,
+                _ => {
+                    return Err(IsoParseError::Unsupported(
+                        "start and end of different type",
+                    ))
+                }
+            },
+        };
+
+        if duration.is_zero() {
+            return Err(IsoParseError::EmptyDuration);
+        }
+
+        Ok(Self {
+            repetitions,
+            start,
+            duration,
+        })
+    }
+}
+
+fn exact_duration(duration: Duration) -> Result<IsoDuration, IsoParseError> {
+    let seconds =
+        u32::try_from(duration.num_seconds()).map_err(|_| IsoParseError::EndBeforeStart)?;
+
+    Ok(IsoDuration {
+        hours: seconds / 3600,
+        minutes: seconds % 3600 / 60,
+        seconds: seconds % 60,
+        ..Default::default()
+    })
+}
+
+impl fmt::Display for RepeatingInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.repetitions {
+            Some(repetitions) => write!(f, "R{}/", repetitions)?,
+            None => write!(f, "R/")?,
+        }
+
+        match self.start {
+            Dt::Date(date) => write!(f, "{}", date.format("%Y-%m-%d"))?,
+            Dt::DateTimeLocal(datetime) => write!(f, "{}", datetime.format("%Y-%m-%dT%H:%M:%S"))?,
+            Dt::DateTimeUtc(datetime) => write!(f, "{}", datetime.format("%Y-%m-%dT%H:%M:%SZ"))?,
+        }
+
+        write!(f, "/{}", self.duration)
+    }
+}
+
+/// Iterator over the starts of a [`RepeatingInterval`]
+#[derive(Debug)]
+pub struct RepeatingIntervalIter {
+    start: DateTime<DefaultZone>,
+    tz: Option<DefaultZone>,
+    duration: IsoDuration,
+    remaining: Option<u32>,
+    n: u32,
+}
+
+impl Iterator for RepeatingIntervalIter {
+    type Item = RRuleIterYield;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(remaining) = &mut self.remaining {
+            if *remaining == 0 {
+                return None;
+            }
+
+            *remaining -= 1;
+        }
+
+        // Always add to the start instead of the previous value,
+        // so that month ends are not clamped repeatedly
+        let datetime = self.duration.add_to(self.start, self.n)?;
+        self.n = self.n.checked_add(1)?;
+
+        Some(if self.tz.is_some() {
+            RRuleIterYield::DateTimeTz(datetime)
+        } else {
+            RRuleIterYield::DateTimeLocal(datetime.naive_local())
+        })
+    }
+}
+
+impl RRule {
+    /// Convert to an ISO 8601 repeating interval
+    ///
+    /// Only rules without BYxxx parts and UNTIL can be converted. Rules in a timezone other than UTC
+    /// are only supported for sub-daily frequencies, as ISO 8601 has no notion of timezones. Monthly
+    /// and yearly rules starting after the 28th are rejected, as ISO 8601 clamps missing days.
+    pub fn to_iso8601(&self) -> Result<String, IsoFormatError> {
+        let recur = &self.recur;
+
+        if recur.until.is_some() {
+            return Err(IsoFormatError::Unsupported("UNTIL"));
+        }
+
+        if !recur.by_second.is_empty()
+            || !recur.by_minute.is_empty()
+            || !recur.by_hour.is_empty()
+            || !recur.by_day.is_empty()
+            || !recur.by_month_day.is_empty()
+            || !recur.by_year_day.is_empty()
+            || !recur.by_week_no.is_empty()
+            || !recur.by_month.is_empty()
+            || !recur.by_set_pos.is_empty()
+        {
+            return Err(IsoFormatError::Unsupported("BYxxx"));
+        }
+
+        let start = match (self.dt_start.0.tz, self.dt_start.0.dt) {
+            (None, dt) => dt,
+            (Some(tz), dt) if tz == DefaultZone::utc() => dt,
+            (Some(tz), dt) => {
+                if !recur.freq.is_sub_daily() {
+                    return Err(IsoFormatError::Unsupported("TZID"));
+                }
+
+                let naive = match dt {
+                    Dt::Date(date) => date.and_time(NaiveTime::MIN),
+                    Dt::DateTimeLocal(datetime) => datetime,
+                    Dt::DateTimeUtc(datetime) => datetime.naive_utc(),
+                };
+
+                let datetime = match dt {
+                    Dt::DateTimeUtc(datetime) => datetime,
+                    _ => local_datetime_with_tz(naive, tz).with_timezone(&Utc),
+                };
+
+                Dt::DateTimeUtc(datetime)
+            }
+        };
+
+        if may_clamp(recur.freq, &start) {
+            return Err(IsoFormatError::Unsupported("day after the 28th"));
+        }
+
+        let n = recur.interval.unwrap_or(1);
+        let mut duration = IsoDuration::default();
+
+        match recur.freq {
+            Frequency::Secondly => duration.seconds = n,
+            Frequency::Minutely => duration.minutes = n,
+            Frequency::Hourly => duration.hours = n,
+            Frequency::Daily => duration.days = n,
+            Frequency::Weekly => duration.weeks = n,
+            Frequency::Monthly => duration.months = n,
+            Frequency::Yearly => duration.years = n,
+        }
+
+        let interval = RepeatingInterval {
+            repetitions: recur.count,
+            start,
+            duration,
+        };
+
+        Ok(interval.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn parse() {
+        let interval: RepeatingInterval = "R5/2008-03-01T13:00:00Z/P1Y2M10DT2H30M".parse().unwrap();
+
+        assert_eq!(interval.repetitions, Some(5));
+        assert_eq!(
+            interval.to_string(),
+            "R5/2008-03-01T13:00:00Z/P1Y2M10DT2H30M"
+        );
+        assert!(interval.to_rrule().is_none());
+
+        let interval: RepeatingInterval = "R/2022-01-03T10:00:00+01:00/PT1H30M".parse().unwrap();
+        let rrule = interval.to_rrule().unwrap();
+
+        assert_eq!(
+            format!("DTSTART{}\nRRULE:{}", rrule.dt_start.0, rrule.recur),
+            "DTSTART:20220103T090000Z\nRRULE:FREQ=MINUTELY;INTERVAL=90"
+        );
+
+        let interval: RepeatingInterval = "R3/2022-01-03/2022-01-17".parse().unwrap();
+        let rrule = interval.to_rrule().unwrap();
+
+        assert_eq!(
+            format!("DTSTART{}\nRRULE:{}", rrule.dt_start.0, rrule.recur),
+            "DTSTART;VALUE=DATE:20220103\nRRULE:FREQ=WEEKLY;COUNT=3;INTERVAL=2"
+        );
+
+        let interval: RepeatingInterval = "R4/2022-01-31T09:00:00/P1M".parse().unwrap();
+        assert!(interval.to_rrule().is_none());
+
+        let interval: RepeatingInterval = "R3/2024-02-29/P1Y".parse().unwrap();
+        assert!(interval.to_rrule().is_none());
+
+        assert!(matches!(
+            "R/2022-01-03/P".parse::<RepeatingInterval>(),
+            Err(IsoParseError::EmptyDuration)
+        ));
+    }
+
+    #[test]
+    fn iter() {
+        let interval: RepeatingInterval = "R3/2022-01-31T10:00:00/P1M1D".parse().unwrap();
+
+        let dates: Vec<_> = interval
+            .iter()
+            .map(|yielded| match yielded {
+                RRuleIterYield::DateTimeLocal(datetime) => datetime.to_string(),
+                RRuleIterYield::DateTimeTz(datetime) => datetime.to_string(),
+            })
+            .collect();
+
+        assert_eq!(
+            dates,
+            [
+                "2022-01-31 10:00:00",
+                "2022-03-01 10:00:00",
+                "2022-04-02 10:00:00"
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn format() {
+        let to_iso8601 = |s: &str| s.parse::<RRule>().unwrap().to_iso8601();
+
+        assert_eq!(
+            to_iso8601("DTSTART:20220103T090000Z\nRRULE:FREQ=MONTHLY;INTERVAL=2;COUNT=5").unwrap(),
+            "R5/2022-01-03T09:00:00Z/P2M"
+        );
+        assert_eq!(
+            to_iso8601("DTSTART;TZID=Europe/Berlin:20220103T090000\nRRULE:FREQ=HOURLY").unwrap(),
+            "R/2022-01-03T08:00:00Z/PT1H"
+        );
+        assert!(matches!(
+            to_iso8601("DTSTART;TZID=Europe/Berlin:20220103T090000\nRRULE:FREQ=DAILY"),
+            Err(IsoFormatError::Unsupported("TZID"))
+        ));
+        assert!(matches!(
+            to_iso8601("DTSTART:20220131T090000\nRRULE:FREQ=MONTHLY;COUNT=4"),
+            Err(IsoFormatError::Unsupported("day after the 28th"))
+        ));
+        assert!(matches!(
+            to_iso8601("DTSTART;VALUE=DATE:20240229\nRRULE:FREQ=YEARLY;COUNT=3"),
+            Err(IsoFormatError::Unsupported("day after the 28th"))
+        ));
+    }
+}
@@ -1,31 +1,40 @@
 use crate::byday::ByDay;
-use crate::dt_prop::local_datetime_with_tz;
+use crate::dt::Dt;
+use crate::dt_prop::{local_datetime_with_tz, DtProperty};
+use crate::equivalence::lcm;
 use crate::freq::Frequency;
 use crate::mappings;
 use crate::recur::Recur;
 use crate::rrule::RRule;
+use crate::rrule_set::RRuleSet;
 use crate::util::{is_leap_year, year_len};
 use crate::weekday::{days_until, Weekday};
-use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Timelike};
-use chrono_tz::Tz;
-use std::cmp::Ordering;
-
-pub struct RRuleIter {
+use crate::zone::{DefaultZone, Zone};
+use alloc::{vec, vec::Vec};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+use core::cmp::{Ordering, Reverse};
+use core::iter::Peekable;
+
+#[derive(Debug)]
+pub struct RRuleIter<Z: Zone = DefaultZone> {
     // recurrence rules
     recur: Recur,
 
-    // DTSTART as DateTime<Tz>
+    // DTSTART as DateTime<Z>
     // If DTSTART is floating the timezone is UTC
-    dt_start: DateTime<Tz>,
+    dt_start: DateTime<Z>,
 
     // Timezone of DTSTART, None if DTSTART is floating
-    dt_start_tz: Option<Tz>,
+    dt_start_tz: Option<Z>,
 
     interval: u32,
     count: Option<u32>,
     week_start: Weekday,
 
-    step: fn(&mut RRuleIter),
+    // UNTIL in the same representation as `RRuleIterYield::sort_key`
+    until: Option<NaiveDateTime>,
+
+    step: fn(&mut RRuleIter<Z>),
 
     hours: Vec<u32>,
     minutes: Vec<u32>,
@@ -49,13 +58,39 @@ pub struct RRuleIter {
 }
 
 #[derive(Debug, Clone, Copy)]
-pub enum RRuleIterYield {
+pub enum RRuleIterYield<Z: Zone = DefaultZone> {
     DateTimeLocal(NaiveDateTime),
-    DateTimeTz(DateTime<Tz>),
+    DateTimeTz(DateTime<Z>),
+}
+
+impl<Z: Zone> RRuleIterYield<Z> {
+    /// Key to order yields by, UTC if the yield has a timezone
+    pub(crate) fn sort_key(&self) -> NaiveDateTime {
+        match self {
+            Self::DateTimeLocal(datetime) => *datetime,
+            Self::DateTimeTz(datetime) => datetime.naive_utc(),
+        }
+    }
+
+    /// Wall clock time of the yield
+    pub(crate) fn local(&self) -> NaiveDateTime {
+        match self {
+            Self::DateTimeLocal(datetime) => *datetime,
+            Self::DateTimeTz(datetime) => datetime.naive_local(),
+        }
+    }
+
+    /// Yield of a date property in a set whose DTSTART has the given timezone
+    pub(crate) fn from_property(dt: DtProperty<Z>, tz: Option<Z>) -> Self {
+        match tz {
+            Some(tz) => Self::DateTimeTz(dt.to_datetime().with_timezone(&tz)),
+            None => Self::DateTimeLocal(dt.to_datetime().naive_local()),
+        }
+    }
 }
 
-impl RRuleIter {
-    pub fn new(rrule: &RRule) -> Self {
+impl<Z: Zone> RRuleIter<Z> {
+    pub fn new(rrule: &RRule<Z>) -> Self {
         let dt_start = rrule.dt_start.0.to_datetime();
         let dt_start_tz = rrule.dt_start.0.result_tz();
 
@@ -124,6 +159,11 @@ impl RRuleIter {
             count: rrule.recur.count,
             week_start: rrule.recur.week_start.unwrap_or(Weekday::Monday),
 
+            until: rrule
+                .recur
+                .until
+                .map(|until| until_sort_key(until, dt_start_tz)),
+
             step,
 
             hours,
@@ -167,6 +207,11 @@ impl RRuleIter {
 
         // == FALLTHROUGH ==
 
+        if this.days.is_empty() {
+            // The rule has no occurrences
+            return this;
+        }
+
         // No viable day in the year to step to.
         // Skip the year by setting every index to max
         // Then just step.
@@ -181,134 +226,159 @@ impl RRuleIter {
     }
 
     fn add_days_yearly(&mut self) {
-        let leap_year = is_leap_year(self.year);
-        let year_len = if leap_year { 366 } else { 365 };
+        let year_len = year_len(self.year) as i32;
+        let recur = &self.recur;
 
         let dt_start_monthday1 = self.dt_start.day();
 
-        // BY MONTH
-        for &month1 in &self.recur.by_month {
-            if !self.recur.by_day.is_empty() {
-                for by_day in &self.recur.by_day {
-                    self.days.extend(by_day.days_in_month(self.year, month1));
+        // Without any day parts the day is taken from DTSTART
+        if recur.by_week_no.is_empty()
+            && recur.by_year_day.is_empty()
+            && recur.by_month_day.is_empty()
+            && recur.by_day.is_empty()
+        {
+            let months = if recur.by_month.is_empty() {
+                vec![self.dt_start.month()]
+            } else {
+                recur.by_month.clone()
+            };
+
+            for month1 in months {
+                if let Some(date) = NaiveDate::from_ymd_opt(self.year, month1, dt_start_monthday1) {
+                    self.days.push(date.ordinal0() as i32);
                 }
-            } else if let Some(date) =
-                NaiveDate::from_ymd_opt(self.year, month1, dt_start_monthday1)
-            {
-                self.days.push(date.ordinal0() as i32);
             }
-        }
-
-        // BY WEEK NO
-        let first_day_of_this_year = NaiveDate::from_yo(self.year, 1);
-        let first_day_of_next_year = NaiveDate::from_yo(self.year + 1, 1);
 
-        let wkd_of_first_day_this_year = first_day_of_this_year.weekday();
-        let wkd_of_first_day_next_year = first_day_of_next_year.weekday();
+            return;
+        }
 
-        let mut wkst_offset_this_year =
-            self.week_start.offset_from(wkd_of_first_day_this_year) as i32;
-        let mut wkst_offset_next_year =
-            self.week_start.offset_from(wkd_of_first_day_next_year) as i32;
+        let year = self.year;
+        let week_days = self.week_no_days();
 
-        if wkst_offset_this_year > 4 {
-            wkst_offset_this_year -= 7;
-        }
+        // Candidates are expanded from the most specific part, all other parts limit them. BYDAY
+        // expands to the weekdays of the year or of each month, next to BYYEARDAY, BYMONTHDAY or
+        // BYWEEKNO it limits the days.
+        let by_day_expands = recur.by_year_day.is_empty()
+            && recur.by_month_day.is_empty()
+            && recur.by_week_no.is_empty();
 
-        if wkst_offset_next_year > 4 {
-            wkst_offset_next_year += year_len - 7;
+        let candidates: Vec<i32> = if by_day_expands && !recur.by_month.is_empty() {
+            recur
+                .by_month
+                .iter()
+                .flat_map(|&month1| {
+                    recur
+                        .by_day
+                        .iter()
+                        .flat_map(move |by_day| by_day.days_in_month(year, month1))
+                })
+                .collect()
+        } else if by_day_expands {
+            recur
+                .by_day
+                .iter()
+                .flat_map(|by_day| by_day.days_in_year(year))
+                .collect()
+        } else if !recur.by_year_day.is_empty() {
+            recur
+                .by_year_day
+                .iter()
+                .map(|&by_year_day| match by_year_day {
+                    1.. => by_year_day - 1,
+                    _ => year_len + by_year_day,
+                })
+                .filter(|yd| (0..year_len).contains(yd))
+                .collect()
+        } else if !recur.by_week_no.is_empty() {
+            self.week_no_days()
         } else {
-            wkst_offset_next_year += year_len;
-        }
+            (0..year_len).collect()
+        };
 
-        for &week_no1 in &self.recur.by_week_no {
-            match week_no1.cmp(&0) {
-                Ordering::Greater => {
-                    let week_no0 = week_no1 - 1;
+        let mut days = vec![];
 
-                    let first_day_of_week = (week_no0 * 7) + wkst_offset_this_year;
+        for yd in candidates {
+            let date = NaiveDate::from_yo_opt(self.year, yd as u32 + 1).expect("valid year day");
+            let month1 = date.month();
 
-                    for mut yd in first_day_of_week..first_day_of_week + 7 {
-                        if yd > year_len {
-                            yd -= year_len;
-                        }
+            if !recur.by_month.is_empty() && !recur.by_month.contains(&month1) {
+                continue;
+            }
 
-                        self.days.push(yd as i32);
-                    }
-                }
-                Ordering::Less => {
-                    let first_day_of_week = wkst_offset_next_year + (week_no1 * 7);
+            if !recur.by_week_no.is_empty() && !week_days.contains(&yd) {
+                continue;
+            }
 
-                    for mut yd in first_day_of_week..first_day_of_week + 7 {
-                        if yd > year_len {
-                            yd -= year_len;
-                        }
+            if !recur.by_month_day.is_empty() {
+                let days_in_month = mappings::days_in_month(is_leap_year(self.year), month1 - 1)
+                    .expect("valid months from 1 to 12") as i32;
+                let day = date.day() as i32;
 
-                        self.days.push(yd as i32);
-                    }
-                }
-                Ordering::Equal => {
-                    unreachable!()
+                if !recur.by_month_day.iter().any(|&by_month_day| {
+                    by_month_day == day || days_in_month + by_month_day + 1 == day
+                }) {
+                    continue;
                 }
             }
-        }
 
-        // BY YEAR DAY
-        for &by_year_day1 in &self.recur.by_year_day {
-            if by_year_day1 > 0 && by_year_day1 < year_len {
-                self.days.push(by_year_day1 - 1);
-            } else if by_year_day1 < 0 && -by_year_day1 < year_len {
-                self.days.push((year_len + by_year_day1) - 1);
+            let by_day_matches = if recur.by_day.is_empty() || by_day_expands {
+                true
+            } else if !recur.by_year_day.is_empty() || !recur.by_month_day.is_empty() {
+                self.by_day_allows(month1, yd)
+            } else {
+                recur.by_day.iter().any(|by_day| match by_day {
+                    ByDay::All(weekday) | ByDay::Nth(weekday, _) => *weekday == date.weekday(),
+                })
+            };
+
+            if by_day_matches {
+                days.push(yd);
             }
         }
 
-        // BY MONTH DAY
-        for &by_month_day in &self.recur.by_month_day {
-            for month0 in 0..12 {
-                let range = if leap_year {
-                    &mappings::MONTH_TO_YEARDAYS_LEAPYEAR[month0]
-                } else {
-                    &mappings::MONTH_TO_YEARDAYS_NORMAL[month0]
-                };
+        self.days.extend(days);
+    }
 
-                let max_days = (range.end - range.start) as i32;
+    /// Days of this year in the weeks of BYWEEKNO
+    fn week_no_days(&self) -> Vec<i32> {
+        let year_len = year_len(self.year) as i32;
 
-                let yearday = if by_month_day > 0 && by_month_day < max_days {
-                    range.start as i32 + by_month_day - 1
-                } else if by_month_day < 0 && -by_month_day < max_days {
-                    range.start as i32 + max_days + by_month_day
-                } else {
-                    continue;
-                };
+        let first_day_of_this_year = NaiveDate::from_yo_opt(self.year, 1).expect("valid year");
+        let first_day_of_next_year = NaiveDate::from_yo_opt(self.year + 1, 1).expect("valid year");
 
-                if self.by_day_allows(month0 as u32 + 1, yearday) {
-                    self.days.push(yearday);
-                }
-            }
+        let wkd_of_first_day_this_year = first_day_of_this_year.weekday();
+        let wkd_of_first_day_next_year = first_day_of_next_year.weekday();
+
+        let mut wkst_offset_this_year =
+            self.week_start.offset_from(wkd_of_first_day_this_year) as i32;
+        let mut wkst_offset_next_year =
+            self.week_start.offset_from(wkd_of_first_day_next_year) as i32;
+
+        if wkst_offset_this_year > 4 {
+            wkst_offset_this_year -= 7;
         }
 
-        // BY DAY
-        if self.recur.by_month_day.is_empty()
-            && self.recur.by_week_no.is_empty()
-            && self.recur.by_month.is_empty()
-        {
-            for by_day in &self.recur.by_day {
-                self.days.extend(by_day.days_in_year(self.year));
-            }
+        if wkst_offset_next_year > 4 {
+            wkst_offset_next_year += year_len - 7;
+        } else {
+            wkst_offset_next_year += year_len;
         }
 
-        // not sure about this
-        if self.days.is_empty() {
-            if let Some(date) =
-                NaiveDate::from_ymd_opt(self.year, self.dt_start.month(), dt_start_monthday1)
-            {
-                self.days.push(date.ordinal0() as i32);
-            } else {
-                // oof recursion
-                self.year += 1;
-                self.add_days_yearly();
-            }
+        let mut days = vec![];
+
+        for &week_no1 in &self.recur.by_week_no {
+            let first_day_of_week = match week_no1.cmp(&0) {
+                Ordering::Greater => ((week_no1 - 1) * 7) + wkst_offset_this_year,
+                Ordering::Less => wkst_offset_next_year + (week_no1 * 7),
+                Ordering::Equal => unreachable!(),
+            };
+
+            days.extend(
+                (first_day_of_week..first_day_of_week + 7).filter(|yd| (0..year_len).contains(yd)),
+            );
         }
+
+        days
     }
 
     fn add_days_monthly(&mut self) {
@@ -361,7 +431,7 @@ impl RRuleIter {
     fn add_days_weekly(&mut self) {
         let leap_year = is_leap_year(self.year);
 
-        let first_day = NaiveDate::from_yo(self.year, 1);
+        let first_day = NaiveDate::from_yo_opt(self.year, 1).expect("valid year");
         let first_weekday = first_day.weekday();
 
         let weekdays = if self.recur.by_day.is_empty() {
@@ -387,13 +457,7 @@ impl RRuleIter {
                 let yd = offset + (i * 7);
 
                 if let Some(month1) = mappings::yearday_to_month(leap_year, yd) {
-                    if self.recur.by_month.is_empty()
-                        || self
-                            .recur
-                            .by_month
-                            .iter()
-                            .any(|&bymonth1| bymonth1 == month1)
-                    {
+                    if self.recur.by_month.is_empty() || self.recur.by_month.contains(&month1) {
                         self.days.push(yd as i32);
                     }
                 } else {
@@ -465,16 +529,32 @@ impl RRuleIter {
     }
 
     fn rebuild_days(&mut self) {
-        self.days.clear();
-
-        match self.recur.freq {
-            Frequency::Secondly => self.add_days_hourly_minutely_secondly(),
-            Frequency::Minutely => self.add_days_hourly_minutely_secondly(),
-            Frequency::Hourly => self.add_days_hourly_minutely_secondly(),
-            Frequency::Daily => self.add_days_daily(),
-            Frequency::Weekly => self.add_days_weekly(),
-            Frequency::Monthly => self.add_days_monthly(),
-            Frequency::Yearly => self.add_days_yearly(),
+        let start_year = self.year;
+
+        loop {
+            self.days.clear();
+
+            match self.recur.freq {
+                Frequency::Secondly => self.add_days_hourly_minutely_secondly(),
+                Frequency::Minutely => self.add_days_hourly_minutely_secondly(),
+                Frequency::Hourly => self.add_days_hourly_minutely_secondly(),
+                Frequency::Daily => self.add_days_daily(),
+                Frequency::Weekly => self.add_days_weekly(),
+                Frequency::Monthly => self.add_days_monthly(),
+                Frequency::Yearly => self.add_days_yearly(),
+            }
+
+            if !self.days.is_empty() {
+                break;
+            }
+
+            // The calendar repeats every 400 years, a rule without days in that time has none
+            if self.year - start_year >= 400 || self.year >= NaiveDate::MAX.year() - 1 {
+                self.year = NaiveDate::MAX.year() - 1;
+                return;
+            }
+
+            self.year += 1;
         }
 
         self.days.sort_unstable();
@@ -497,7 +577,7 @@ impl RRuleIter {
         }
     }
 
-    fn to_yield(&self, datetime: NaiveDateTime) -> RRuleIterYield {
+    fn to_yield(&self, datetime: NaiveDateTime) -> RRuleIterYield<Z> {
         if let Some(tz) = self.dt_start_tz {
             RRuleIterYield::DateTimeTz(local_datetime_with_tz(datetime, tz))
         } else {
@@ -506,8 +586,8 @@ impl RRuleIter {
     }
 }
 
-impl Iterator for RRuleIter {
-    type Item = RRuleIterYield;
+impl<Z: Zone> Iterator for RRuleIter<Z> {
+    type Item = RRuleIterYield<Z>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(count) = &mut self.count {
@@ -521,7 +601,7 @@ impl Iterator for RRuleIter {
         // TODO this panic protection is insufficient
         // as rules like every 29th Feb on a Sunday
         // step multiple years inside a single `step` call
-        if self.year == chrono::MAX_DATE.year() - 1 {
+        if self.year == NaiveDate::MAX.year() - 1 {
             return None;
         }
 
@@ -569,18 +649,190 @@ impl Iterator for RRuleIter {
             )
         };
 
-        let datetime =
-            NaiveDate::from_ymd(year, month as u32, day as u32).and_hms(hour, minute, second);
+        let datetime = NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+            .and_then(|date| match second {
+                // Leap second
+                60 => date.and_hms_milli_opt(hour, minute, 59, 1_000),
+                _ => date.and_hms_opt(hour, minute, second),
+            })
+            .expect("valid date and time");
 
         for _ in 0..self.interval {
             (self.step)(self);
         }
 
-        Some(self.to_yield(datetime))
+        let yielded = self.to_yield(datetime);
+
+        if self.until.is_some_and(|until| yielded.sort_key() > until) {
+            self.count = Some(0);
+            return None;
+        }
+
+        Some(yielded)
+    }
+}
+
+/// Key of UNTIL to compare with [`RRuleIterYield::sort_key`]
+pub(crate) fn until_sort_key<Z: Zone>(until: Dt, dt_start_tz: Option<Z>) -> NaiveDateTime {
+    let local = |datetime: NaiveDateTime| match dt_start_tz {
+        Some(tz) => local_datetime_with_tz(datetime, tz).naive_utc(),
+        None => datetime,
+    };
+
+    match until {
+        // A DATE includes the whole day
+        Dt::Date(date) => local(date.and_hms_opt(23, 59, 59).expect("valid time")),
+        Dt::DateTimeLocal(datetime) => local(datetime),
+        Dt::DateTimeUtc(datetime) => datetime.naive_utc(),
+    }
+}
+
+/// Iterator over the occurrences of a [`RRuleSet`], in order and without duplicates
+#[derive(Debug)]
+pub struct RRuleSetIter<Z: Zone = DefaultZone> {
+    rrules: Vec<Peekable<RRuleIter<Z>>>,
+    exrules: Vec<Peekable<RRuleIter<Z>>>,
+
+    // sorted in reverse to pop the earliest
+    rdates: Vec<RRuleIterYield<Z>>,
+    exdates: Vec<NaiveDateTime>,
+
+    last: Option<NaiveDateTime>,
+
+    // Length after which the rules repeat, if the EXRULEs are unbounded
+    cycle: Option<Duration>,
+    // Local time of the first of the occurrences excluded in a row
+    excluded_since: Option<NaiveDateTime>,
+}
+
+impl<Z: Zone> RRuleSetIter<Z> {
+    pub fn new(set: &RRuleSet<Z>) -> Self {
+        let dt_start_tz = set.dt_start.0.result_tz();
+
+        let iter =
+            |recur: &Recur| RRuleIter::new(&RRule::new(set.dt_start, recur.clone())).peekable();
+
+        let to_yield = |dt: DtProperty<Z>| RRuleIterYield::from_property(dt, dt_start_tz);
+
+        let mut rdates: Vec<_> = set.rdates.iter().map(|rdate| to_yield(rdate.0)).collect();
+        rdates.sort_by_key(|rdate| Reverse(rdate.sort_key()));
+
+        let mut exdates: Vec<_> = set
+            .exdates
+            .iter()
+            .map(|exdate| to_yield(exdate.0).sort_key())
+            .collect();
+        exdates.sort_unstable();
+
+        let cycle = if set.exrules.is_empty()
+            || set
+                .exrules
+                .iter()
+                .any(|recur| recur.count.is_some() || recur.until.is_some())
+        {
+            None
+        } else {
+            set.rrules
+                .iter()
+                .chain(&set.exrules)
+                .try_fold(1, |cycle, recur| lcm(cycle, recur.cycle()?))
+                .and_then(Duration::try_seconds)
+        };
+
+        Self {
+            rrules: set.rrules.iter().map(iter).collect(),
+            exrules: set.exrules.iter().map(iter).collect(),
+            rdates,
+            exdates,
+            last: None,
+            cycle,
+            excluded_since: None,
+        }
+    }
+
+    fn next_included(&mut self) -> Option<RRuleIterYield<Z>> {
+        let rrule = self
+            .rrules
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, iter)| Some((i, iter.peek()?.sort_key())))
+            .min_by_key(|(_, key)| *key);
+
+        match (rrule, self.rdates.last()) {
+            (Some((_, key)), Some(rdate)) if rdate.sort_key() <= key => self.rdates.pop(),
+            (Some((i, _)), _) => self.rrules[i].next(),
+            (None, _) => self.rdates.pop(),
+        }
+    }
+
+    fn is_excluded(&mut self, key: NaiveDateTime) -> bool {
+        if self.exdates.binary_search(&key).is_ok() {
+            return true;
+        }
+
+        self.exrules.iter_mut().any(|iter| {
+            while iter.next_if(|exrule| exrule.sort_key() < key).is_some() {}
+
+            iter.peek().is_some_and(|exrule| exrule.sort_key() == key)
+        })
+    }
+
+    /// Check if the EXRULEs exclude every remaining occurrence, given the excluded `occurrence`
+    ///
+    /// Past all RDATEs and EXDATEs the occurrences of the rules repeat after their common cycle,
+    /// so once a whole cycle is excluded, all later occurrences are excluded too.
+    fn excludes_all(&mut self, occurrence: RRuleIterYield<Z>) -> bool {
+        let Some(cycle) = self.cycle else {
+            return false;
+        };
+
+        if !self.rdates.is_empty()
+            || self
+                .exdates
+                .last()
+                .is_some_and(|exdate| *exdate >= occurrence.sort_key())
+        {
+            self.excluded_since = None;
+            return false;
+        }
+
+        let local = occurrence.local();
+        let since = *self.excluded_since.get_or_insert(local);
+
+        local - since >= cycle
     }
 }
 
-fn step_second(this: &mut RRuleIter) {
+impl<Z: Zone> Iterator for RRuleSetIter<Z> {
+    type Item = RRuleIterYield<Z>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let next = self.next_included()?;
+            let key = next.sort_key();
+
+            if self.last == Some(key) {
+                continue;
+            }
+
+            if self.is_excluded(key) {
+                if self.excludes_all(next) {
+                    self.rrules.clear();
+                    return None;
+                }
+
+                continue;
+            }
+
+            self.last = Some(key);
+            self.excluded_since = None;
+
+            return Some(next);
+        }
+    }
+}
+
+fn step_second<Z: Zone>(this: &mut RRuleIter<Z>) {
     this.seconds_idx += 1;
 
     if this.seconds_idx == this.seconds.len() {
@@ -589,7 +841,7 @@ fn step_second(this: &mut RRuleIter) {
     }
 }
 
-fn step_minute(this: &mut RRuleIter) {
+fn step_minute<Z: Zone>(this: &mut RRuleIter<Z>) {
     this.minutes_idx += 1;
 
     if this.minutes_idx == this.minutes.len() {
@@ -598,7 +850,7 @@ fn step_minute(this: &mut RRuleIter) {
     }
 }
 
-fn step_hour(this: &mut RRuleIter) {
+fn step_hour<Z: Zone>(this: &mut RRuleIter<Z>) {
     this.hours_idx += 1;
 
     if this.hours_idx == this.hours.len() {
@@ -607,7 +859,7 @@ fn step_hour(this: &mut RRuleIter) {
     }
 }
 
-fn step_day(this: &mut RRuleIter) {
+fn step_day<Z: Zone>(this: &mut RRuleIter<Z>) {
     this.days_idx += 1;
 
     if this.days_idx == this.days.len() {
@@ -648,10 +900,10 @@ fn days<'a>(
             && !by_month_day.iter().any(|&monthday| {
                 if monthday > 0 {
                     monthday as u32 == day1
-                } else if monthday < 0 && -monthday < days_in_month as i32 {
-                    let monthday = (days_in_month as i32 - monthday) as u32;
+                } else if monthday < 0 && -monthday <= days_in_month as i32 {
+                    let monthday = (days_in_month as i32 + monthday + 1) as u32;
 
-                    monthday as u32 == day1
+                    monthday == day1
                 } else {
                     false
                 }
@@ -680,3 +932,87 @@ fn days<'a>(
         Some(date.ordinal0())
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dates(s: &str, n: usize) -> Vec<NaiveDate> {
+        let rrule: RRule = s.parse().unwrap();
+
+        RRuleIter::new(&rrule)
+            .take(n)
+            .map(|yielded| match yielded {
+                RRuleIterYield::DateTimeLocal(datetime) => datetime.date(),
+                RRuleIterYield::DateTimeTz(datetime) => datetime.date_naive(),
+            })
+            .collect()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn yearly_parts_intersect() {
+        assert_eq!(
+            dates(
+                "DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYMONTH=1;BYMONTHDAY=3",
+                2
+            ),
+            [date(2022, 1, 3), date(2023, 1, 3)]
+        );
+        assert_eq!(
+            dates(
+                "DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYMONTHDAY=13;BYDAY=FR",
+                2
+            ),
+            [date(2022, 5, 13), date(2023, 1, 13)]
+        );
+    }
+
+    #[test]
+    fn yearly_last_days() {
+        assert_eq!(
+            dates(
+                "DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYMONTH=1,2,3;BYMONTHDAY=31",
+                3
+            ),
+            [date(2022, 1, 31), date(2022, 3, 31), date(2023, 1, 31)]
+        );
+        assert_eq!(
+            dates(
+                "DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=-1",
+                2
+            ),
+            [date(2022, 2, 28), date(2023, 2, 28)]
+        );
+        assert_eq!(
+            dates("DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYYEARDAY=-1", 2),
+            [date(2022, 12, 31), date(2023, 12, 31)]
+        );
+    }
+
+    #[test]
+    fn yearly_without_occurrences() {
+        // Years without an occurrence are skipped
+        assert_eq!(
+            dates(
+                "DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29",
+                2
+            ),
+            [date(2024, 2, 29), date(2028, 2, 29)]
+        );
+        assert_eq!(
+            dates("DTSTART:20220131T090000\nRRULE:FREQ=YEARLY;BYMONTH=2", 1),
+            []
+        );
+        assert_eq!(
+            dates(
+                "DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30",
+                1
+            ),
+            []
+        );
+    }
+}
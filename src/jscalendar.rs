@@ -0,0 +1,538 @@
+use crate::byday::ByDay;
+use crate::dt::Dt;
+use crate::dt_prop::{local_datetime_with_tz, DtProperty, DtStart, ExDate, RDate};
+use crate::freq::Frequency;
+use crate::recur::Recur;
+use crate::rrule_set::RRuleSet;
+use crate::weekday::Weekday;
+use crate::zone::Zone;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use chrono::{NaiveDateTime, NaiveTime, TimeZone, Utc};
+use core::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, thiserror::Error)]
+pub enum JsCalendarError {
+    #[error("unknown timezone '{0}'")]
+    UnknownTimezone(String),
+    #[error("invalid {0} value '{1}'")]
+    InvalidValue(&'static str, String),
+    #[error("{0} is not supported")]
+    Unsupported(&'static str),
+}
+
+/// `@type` markers of JSCalendar objects, optional when deserializing
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum RecurrenceRuleType {
+    #[default]
+    RecurrenceRule,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum NDayType {
+    #[default]
+    NDay,
+}
+
+/// JSCalendar `RecurrenceRule` object, see [RFC8984#4.3.3]
+///
+/// [RFC8984#4.3.3]: https://datatracker.ietf.org/doc/html/rfc8984#section-4.3.3
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrenceRule {
+    #[serde(rename = "@type", default)]
+    js_type: RecurrenceRuleType,
+    #[serde(with = "frequency")]
+    pub frequency: Frequency,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rscale: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip: Option<Skip>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "optional_weekday"
+    )]
+    pub first_day_of_week: Option<Weekday>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_day: Vec<NDay>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_month_day: Vec<i32>,
+    /// Months as strings, leap months of other calendars are suffixed with `L`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_month: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_year_day: Vec<i32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_week_no: Vec<i32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_hour: Vec<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_minute: Vec<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_second: Vec<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_set_position: Vec<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    /// Local date-time in the timezone of the recurring object
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<NaiveDateTime>,
+}
+
+/// Behavior for invalid dates such as Feb 30 with a non gregorian `rscale`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Skip {
+    Omit,
+    Backward,
+    Forward,
+}
+
+/// JSCalendar `NDay` object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NDay {
+    #[serde(rename = "@type", default)]
+    js_type: NDayType,
+    #[serde(with = "weekday")]
+    pub day: Weekday,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nth_of_period: Option<i32>,
+}
+
+/// Recurrence related properties of a JSCalendar `Event` or `Task`
+///
+/// Unrelated properties are ignored when deserializing a full object.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recurrence {
+    pub start: NaiveDateTime,
+    /// `None` for floating time
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    pub show_without_time: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recurrence_rules: Vec<RecurrenceRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_recurrence_rules: Vec<RecurrenceRule>,
+    /// Patches keyed by the recurrence id, `{"excluded": true}` removes an occurrence
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub recurrence_overrides: BTreeMap<NaiveDateTime, serde_json::Map<String, serde_json::Value>>,
+}
+
+mod frequency {
+    use super::*;
+
+    pub(super) fn serialize<S: Serializer>(freq: &Frequency, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&freq.to_string().to_lowercase())
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Frequency, D::Error> {
+        let value = String::deserialize(d)?;
+
+        match Frequency::parse(&value.to_uppercase()) {
+            Ok(("", freq)) => Ok(freq),
+            _ => Err(serde::de::Error::custom(format!(
+                "invalid frequency '{}'",
+                value
+            ))),
+        }
+    }
+}
+
+mod weekday {
+    use super::*;
+
+    pub(super) fn serialize<S: Serializer>(weekday: &Weekday, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&weekday.to_string().to_lowercase())
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Weekday, D::Error> {
+        let value = String::deserialize(d)?;
+
+        match Weekday::parse(&value.to_uppercase()) {
+            Ok(("", weekday)) => Ok(weekday),
+            _ => Err(serde::de::Error::custom(format!(
+                "invalid weekday '{}'",
+                value
+            ))),
+        }
+    }
+}
+
+mod optional_weekday {
+    use super::*;
+
+    pub(super) fn serialize<S: Serializer>(
+        weekday: &Option<Weekday>,
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        match weekday {
+            Some(weekday) => super::weekday::serialize(weekday, s),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        d: D,
+    ) -> Result<Option<Weekday>, D::Error> {
+        super::weekday::deserialize(d).map(Some)
+    }
+}
+
+impl RecurrenceRule {
+    /// Convert a [`Recur`], UNTIL is converted into the local time of `dt_start`
+    pub fn from_recur<Z: Zone>(recur: &Recur, dt_start: &DtProperty<Z>) -> Self {
+        let until = recur.until.map(|until| match until {
+            Dt::Date(date) => date.and_time(NaiveTime::MIN),
+            Dt::DateTimeLocal(datetime) => datetime,
+            Dt::DateTimeUtc(datetime) => datetime
+                .with_timezone(&dt_start.tz.unwrap_or_else(Z::utc))
+                .naive_local(),
+        });
+
+        Self {
+            js_type: RecurrenceRuleType::default(),
+            frequency: recur.freq,
+            interval: recur.interval,
+            rscale: None,
+            skip: None,
+            first_day_of_week: recur.week_start,
+            by_day: recur
+                .by_day
+                .iter()
+                .map(|by_day| match *by_day {
+                    ByDay::All(day) => NDay::new(day, None),
+                    ByDay::Nth(day, nth) => NDay::new(day, Some(nth)),
+                })
+                .collect(),
+            by_month_day: recur.by_month_day.clone(),
+            by_month: recur.by_month.iter().map(u32::to_string).collect(),
+            by_year_day: recur.by_year_day.clone(),
+            by_week_no: recur.by_week_no.clone(),
+            by_hour: recur.by_hour.clone(),
+            by_minute: recur.by_minute.clone(),
+            by_second: recur.by_second.clone(),
+            by_set_position: recur.by_set_pos.clone(),
+            count: recur.count,
+            until,
+        }
+    }
+
+    /// Convert to a [`Recur`], UNTIL is retyped to match `dt_start`
+    ///
+    /// Only the gregorian `rscale` is supported, which makes `skip` meaningless unless it is `omit`.
+    pub fn to_recur<Z: Zone>(&self, dt_start: &DtProperty<Z>) -> Result<Recur, JsCalendarError> {
+        if let Some(rscale) = &self.rscale {
+            if !rscale.eq_ignore_ascii_case("gregorian") {
+                return Err(JsCalendarError::Unsupported("rscale other than gregorian"));
+            }
+        }
+
+        if self.skip.is_some_and(|skip| skip != Skip::Omit) {
+            return Err(JsCalendarError::Unsupported("skip other than omit"));
+        }
+
+        let mut recur = Recur::new(self.frequency);
+
+        recur.interval = self.interval;
+        recur.count = self.count;
+        recur.week_start = self.first_day_of_week;
+        recur.by_day = self
+            .by_day
+            .iter()
+            .map(|n_day| match n_day.nth_of_period {
+                Some(nth) => ByDay::Nth(n_day.day, nth),
+                None => ByDay::All(n_day.day),
+            })
+            .collect();
+        recur.by_month_day = self.by_month_day.clone();
+        recur.by_year_day = self.by_year_day.clone();
+        recur.by_week_no = self.by_week_no.clone();
+        recur.by_hour = self.by_hour.clone();
+        recur.by_minute = self.by_minute.clone();
+        recur.by_second = self.by_second.clone();
+        recur.by_set_pos = self.by_set_position.clone();
+
+        for month in &self.by_month {
+            if month.ends_with('L') {
+                return Err(JsCalendarError::Unsupported("leap months"));
+            }
+
+            let month = u32::from_str(month)
+                .map_err(|_| JsCalendarError::InvalidValue("byMonth", month.clone()))?;
+
+            recur.by_month.push(month);
+        }
+
+        recur.until = self.until.map(|until| match (dt_start.dt, dt_start.tz) {
+            (Dt::Date(_), _) => Dt::Date(until.date()),
+            (Dt::DateTimeLocal(_), None) => Dt::DateTimeLocal(until),
+            (Dt::DateTimeLocal(_), Some(tz)) => {
+                Dt::DateTimeUtc(local_datetime_with_tz(until, tz).with_timezone(&Utc))
+            }
+            (Dt::DateTimeUtc(_), _) => Dt::DateTimeUtc(Utc.from_utc_datetime(&until)),
+        });
+
+        Ok(recur)
+    }
+}
+
+impl NDay {
+    pub fn new(day: Weekday, nth_of_period: Option<i32>) -> Self {
+        Self {
+            js_type: NDayType::default(),
+            day,
+            nth_of_period,
+        }
+    }
+}
+
+impl Recurrence {
+    /// Convert `start` and `timeZone` into a DTSTART-like property
+    fn dt_property<Z: Zone>(&self, local: NaiveDateTime) -> Result<DtProperty<Z>, JsCalendarError> {
+        if self.show_without_time {
+            return Ok(DtProperty {
+                dt: Dt::Date(local.date()),
+                tz: None,
+            });
+        }
+
+        let tz = match &self.time_zone {
+            Some(tz) => {
+                Some(Z::from_tzid(tz).ok_or_else(|| JsCalendarError::UnknownTimezone(tz.clone()))?)
+            }
+            None => None,
+        };
+
+        Ok(match tz {
+            Some(tz) if tz.is_utc() => DtProperty {
+                dt: Dt::DateTimeUtc(Utc.from_utc_datetime(&local)),
+                tz: None,
+            },
+            tz => DtProperty {
+                dt: Dt::DateTimeLocal(local),
+                tz,
+            },
+        })
+    }
+
+    /// Convert to an [`RRuleSet`]
+    ///
+    /// Overrides marked as `excluded` become EXDATEs, all other overrides become RDATEs,
+    /// as they either add an occurrence or patch an existing one. Overrides patching `start`
+    /// move the occurrence, they become an EXDATE at the recurrence id and an RDATE at the new
+    /// start.
+    pub fn to_rrule_set<Z: Zone>(&self) -> Result<RRuleSet<Z>, JsCalendarError> {
+        let dt_start = self.dt_property(self.start)?;
+        let mut set = RRuleSet::new(DtStart(dt_start));
+
+        for rule in &self.recurrence_rules {
+            set.rrules.push(rule.to_recur(&dt_start)?);
+        }
+
+        for rule in &self.excluded_recurrence_rules {
+            set.exrules.push(rule.to_recur(&dt_start)?);
+        }
+
+        for (&recurrence_id, patch) in &self.recurrence_overrides {
+            let dt = self.dt_property(recurrence_id)?;
+
+            if patch.get("excluded") == Some(&serde_json::Value::Bool(true)) {
+                set.exdates.push(ExDate(dt));
+                continue;
+            }
+
+            let start = match patch.get("start") {
+                Some(start) => serde_json::from_value(start.clone())
+                    .map_err(|_| JsCalendarError::InvalidValue("start", start.to_string()))?,
+                None => recurrence_id,
+            };
+
+            if start != recurrence_id {
+                set.exdates.push(ExDate(dt));
+            }
+
+            set.rdates.push(RDate(self.dt_property(start)?));
+        }
+
+        Ok(set)
+    }
+
+    /// Convert from an [`RRuleSet`], RDATEs and EXDATEs are converted into overrides
+    pub fn from_rrule_set<Z: Zone>(set: &RRuleSet<Z>) -> Self {
+        let dt_start = set.dt_start.0;
+
+        let time_zone = match (dt_start.tz, dt_start.dt) {
+            (_, Dt::Date(_)) => None,
+            (Some(tz), _) => Some(tz.tzid()),
+            (None, Dt::DateTimeUtc(_)) => Some("Etc/UTC".into()),
+            (None, Dt::DateTimeLocal(_)) => None,
+        };
+
+        // Convert into the local time of DTSTART, floating values are taken as is
+        let local = |dt: DtProperty<Z>| match dt_start.result_tz() {
+            Some(tz) => dt.to_datetime().with_timezone(&tz).naive_local(),
+            None => dt.to_datetime().naive_local(),
+        };
+
+        let mut recurrence_overrides = BTreeMap::new();
+
+        for rdate in &set.rdates {
+            recurrence_overrides.insert(local(rdate.0), serde_json::Map::new());
+        }
+
+        for exdate in &set.exdates {
+            let mut patch = serde_json::Map::new();
+            patch.insert("excluded".into(), serde_json::Value::Bool(true));
+
+            recurrence_overrides.insert(local(exdate.0), patch);
+        }
+
+        Self {
+            start: local(dt_start),
+            time_zone,
+            show_without_time: dt_start.dt.is_date(),
+            recurrence_rules: set
+                .rrules
+                .iter()
+                .map(|recur| RecurrenceRule::from_recur(recur, &dt_start))
+                .collect(),
+            excluded_recurrence_rules: set
+                .exrules
+                .iter()
+                .map(|recur| RecurrenceRule::from_recur(recur, &dt_start))
+                .collect(),
+            recurrence_overrides,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn to_rrule_set() {
+        let recurrence: Recurrence = serde_json::from_str(
+            r#"{
+                "@type": "Event",
+                "title": "Standup",
+                "start": "2022-01-03T09:00:00",
+                "timeZone": "Europe/Berlin",
+                "recurrenceRules": [{
+                    "@type": "RecurrenceRule",
+                    "frequency": "weekly",
+                    "byDay": [{ "@type": "NDay", "day": "mo" }, { "day": "we" }],
+                    "firstDayOfWeek": "su",
+                    "until": "2022-03-01T00:00:00"
+                }],
+                "excludedRecurrenceRules": [{ "frequency": "monthly", "byDay": [{ "day": "mo", "nthOfPeriod": 1 }] }],
+                "recurrenceOverrides": {
+                    "2022-01-12T09:00:00": { "excluded": true },
+                    "2022-01-14T12:00:00": { "title": "Extra standup" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let set: RRuleSet = recurrence.to_rrule_set().unwrap();
+
+        assert_eq!(
+            set.to_string(),
+            "DTSTART;TZID=Europe/Berlin:20220103T090000\n\
+            RRULE:FREQ=WEEKLY;UNTIL=20220228T230000Z;BYDAY=MO,WE;WKST=SU\n\
+            EXRULE:FREQ=MONTHLY;BYDAY=1MO\n\
+            RDATE;TZID=Europe/Berlin:20220114T120000\n\
+            EXDATE;TZID=Europe/Berlin:20220112T090000"
+        );
+
+        let converted = Recurrence::from_rrule_set(&set);
+
+        assert_eq!(converted.recurrence_rules, recurrence.recurrence_rules);
+        assert_eq!(
+            converted.excluded_recurrence_rules,
+            recurrence.excluded_recurrence_rules
+        );
+        assert_eq!(converted.start, recurrence.start);
+        assert_eq!(converted.time_zone, recurrence.time_zone);
+        assert_eq!(converted.recurrence_overrides.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn moved_override() {
+        let recurrence: Recurrence = serde_json::from_str(
+            r#"{
+                "start": "2022-01-03T09:00:00",
+                "timeZone": "Europe/Berlin",
+                "recurrenceRules": [{ "frequency": "daily", "count": 3 }],
+                "recurrenceOverrides": {
+                    "2022-01-04T09:00:00": { "start": "2022-01-04T11:30:00" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let set: RRuleSet = recurrence.to_rrule_set().unwrap();
+
+        assert_eq!(
+            set.to_string(),
+            "DTSTART;TZID=Europe/Berlin:20220103T090000\n\
+            RRULE:FREQ=DAILY;COUNT=3\n\
+            RDATE;TZID=Europe/Berlin:20220104T113000\n\
+            EXDATE;TZID=Europe/Berlin:20220104T090000"
+        );
+
+        let recurrence: Recurrence = serde_json::from_str(
+            r#"{
+                "start": "2022-01-03T09:00:00",
+                "recurrenceOverrides": { "2022-01-04T09:00:00": { "start": "noon" } }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            recurrence.to_rrule_set::<Utc>(),
+            Err(JsCalendarError::InvalidValue("start", _))
+        ));
+    }
+
+    #[test]
+    fn serialize() {
+        let set: RRuleSet = "DTSTART;VALUE=DATE:20220131\nRRULE:FREQ=MONTHLY;BYMONTHDAY=-1;COUNT=3"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&Recurrence::from_rrule_set(&set)).unwrap(),
+            r#"{"start":"2022-01-31T00:00:00","timeZone":null,"showWithoutTime":true,"recurrenceRules":[{"@type":"RecurrenceRule","frequency":"monthly","byMonthDay":[-1],"count":3}]}"#
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        let rule: RecurrenceRule = serde_json::from_str(
+            r#"{ "frequency": "yearly", "rscale": "chinese", "skip": "forward" }"#,
+        )
+        .unwrap();
+
+        let dt_start: DtProperty = DtProperty {
+            dt: Dt::DateTimeUtc(Utc.with_ymd_and_hms(2022, 1, 3, 9, 0, 0).unwrap()),
+            tz: None,
+        };
+
+        assert!(matches!(
+            rule.to_recur(&dt_start),
+            Err(JsCalendarError::Unsupported(_))
+        ));
+    }
+}
@@ -1,11 +1,37 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod byday;
+pub mod conflict;
+pub mod cron;
+pub mod describe;
+pub mod diff;
 pub mod dt;
 pub mod dt_prop;
+pub mod duration;
+pub mod edit;
+pub mod equivalence;
 pub mod error;
+pub mod freebusy;
 pub mod freq;
+pub mod graph;
+pub mod interval;
+pub mod iso8601;
 pub mod iter;
+pub mod jscalendar;
+pub mod lint;
+pub mod locale;
 pub mod mappings;
+pub mod phrase;
 pub mod recur;
 pub mod rrule;
+pub mod rrule_set;
+pub mod simplify;
+pub mod slot;
+pub mod split;
+pub mod systemd;
 mod util;
+pub mod vevent;
 pub mod weekday;
+pub mod zone;
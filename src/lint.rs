@@ -0,0 +1,385 @@
+use crate::byday::ByDay;
+use crate::dt::Dt;
+use crate::freq::Frequency;
+use crate::iter::RRuleIter;
+use crate::mappings;
+use crate::rrule::{RRule, RRuleVerifyError};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use chrono::Datelike;
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Kind of a [`Diagnostic`], each with a stable code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintCode {
+    InvalidInterval,
+    UntilInvalidValueType,
+    UntilNotLocal,
+    UntilNotUtc,
+    ByTimeInDateRule,
+    ByDayOffsetWithByWeekNo,
+    InvalidValue,
+    NotAllowedInFreq,
+    Unsatisfiable,
+    BySetPosWithoutByX,
+
+    DtStartNotOccurrence,
+    SkippedMonthDay,
+    LargeSpan,
+    UntilBeforeDtStart,
+    ZeroCount,
+}
+
+impl LintCode {
+    /// Stable identifier, `E` prefixed for errors and `W` prefixed for warnings
+    pub fn code(self) -> &'static str {
+        match self {
+            LintCode::InvalidInterval => "E001",
+            LintCode::UntilInvalidValueType => "E002",
+            LintCode::UntilNotLocal => "E003",
+            LintCode::UntilNotUtc => "E004",
+            LintCode::ByTimeInDateRule => "E005",
+            LintCode::ByDayOffsetWithByWeekNo => "E006",
+            LintCode::InvalidValue => "E007",
+            LintCode::NotAllowedInFreq => "E008",
+            LintCode::Unsatisfiable => "E009",
+            LintCode::BySetPosWithoutByX => "E010",
+
+            LintCode::DtStartNotOccurrence => "W001",
+            LintCode::SkippedMonthDay => "W002",
+            LintCode::LargeSpan => "W003",
+            LintCode::UntilBeforeDtStart => "W004",
+            LintCode::ZeroCount => "W005",
+        }
+    }
+
+    pub fn severity(self) -> Severity {
+        if self.code().starts_with('E') {
+            Severity::Error
+        } else {
+            Severity::Warning
+        }
+    }
+}
+
+impl From<&RRuleVerifyError> for LintCode {
+    fn from(err: &RRuleVerifyError) -> Self {
+        match err {
+            RRuleVerifyError::InvalidInterval(_) => LintCode::InvalidInterval,
+            RRuleVerifyError::UntilInvalidValueType => LintCode::UntilInvalidValueType,
+            RRuleVerifyError::UntilNotLocal => LintCode::UntilNotLocal,
+            RRuleVerifyError::UntilNotUtc => LintCode::UntilNotUtc,
+            RRuleVerifyError::StrictByXNotAllowedInDateType => LintCode::ByTimeInDateRule,
+            RRuleVerifyError::ByDayOffSetNotAllowedWithFreqYearlyAndByWeekNo => {
+                LintCode::ByDayOffsetWithByWeekNo
+            }
+            RRuleVerifyError::InvalidValue(..) => LintCode::InvalidValue,
+            RRuleVerifyError::NotAllowedInFreq(..) => LintCode::NotAllowedInFreq,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub code: LintCode,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(code: LintCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.code.severity()
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity() {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+
+        write!(f, "{}[{}]: {}", severity, self.code.code(), self.message)
+    }
+}
+
+/// Spans of COUNT occurrences beyond this many years are reported
+const LARGE_SPAN_YEARS: u64 = 100;
+
+impl RRule {
+    /// Report every problem of the rule, errors first
+    ///
+    /// Errors are violations of RFC 5545 or rules without any occurrence,
+    /// warnings point out rules which likely do not behave as intended.
+    pub fn lint(&self) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<_> = self
+            .verify_all(true)
+            .iter()
+            .map(|err| Diagnostic::new(err.into(), err.to_string()))
+            .collect();
+
+        let recur = &self.recur;
+
+        let has_by_x = !recur.by_second.is_empty()
+            || !recur.by_minute.is_empty()
+            || !recur.by_hour.is_empty()
+            || !recur.by_day.is_empty()
+            || !recur.by_month_day.is_empty()
+            || !recur.by_year_day.is_empty()
+            || !recur.by_week_no.is_empty()
+            || !recur.by_month.is_empty();
+
+        if !recur.by_set_pos.is_empty() && !has_by_x {
+            diagnostics.push(Diagnostic::new(
+                LintCode::BySetPosWithoutByX,
+                "BYSETPOS must be used together with another BYxxx rule part",
+            ));
+        }
+
+        let unsatisfiable = !self.month_days_exist();
+
+        if unsatisfiable {
+            diagnostics.push(Diagnostic::new(
+                LintCode::Unsatisfiable,
+                "none of the months in BYMONTH has any of the days in BYMONTHDAY",
+            ));
+        }
+
+        if let Some(month_days) = self.skipped_month_days() {
+            diagnostics.push(Diagnostic::new(
+                LintCode::SkippedMonthDay,
+                format!(
+                    "months shorter than {} days are skipped",
+                    month_days
+                        .iter()
+                        .map(|day| day.unsigned_abs())
+                        .min()
+                        .unwrap_or(29)
+                ),
+            ));
+        }
+
+        if recur.count == Some(0) {
+            diagnostics.push(Diagnostic::new(
+                LintCode::ZeroCount,
+                "COUNT=0 yields no occurrences",
+            ));
+        }
+
+        if let Some(count) = recur.count {
+            let years = span_years(recur.freq, recur.interval.unwrap_or(1), count);
+
+            if years > LARGE_SPAN_YEARS {
+                diagnostics.push(Diagnostic::new(
+                    LintCode::LargeSpan,
+                    format!("COUNT={} with INTERVAL spans about {} years", count, years),
+                ));
+            }
+        }
+
+        let dt_start = self.dt_start.0.to_datetime();
+
+        if let Some(until) = recur.until {
+            let before = match until {
+                Dt::Date(date) => date < dt_start.date_naive(),
+                Dt::DateTimeLocal(datetime) => datetime < dt_start.naive_local(),
+                Dt::DateTimeUtc(datetime) => datetime < dt_start,
+            };
+
+            if before {
+                diagnostics.push(Diagnostic::new(
+                    LintCode::UntilBeforeDtStart,
+                    "UNTIL is before DTSTART, the rule has no occurrences",
+                ));
+            }
+        }
+
+        let is_error = diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity() == Severity::Error);
+
+        // Iterating invalid rules is not meaningful and may not terminate early, rules with out of
+        // range BYSECOND, BYMINUTE or BYHOUR values cannot be iterated at all
+        if !is_error && !unsatisfiable && recur.count != Some(0) {
+            let first = RRuleIter::new(self).next();
+
+            if first.is_some_and(|first| first.sort_key() != self.dt_start_sort_key()) {
+                diagnostics.push(Diagnostic::new(
+                    LintCode::DtStartNotOccurrence,
+                    "DTSTART does not match the rule and is not its first occurrence",
+                ));
+            }
+        }
+
+        diagnostics.sort_by_key(|diagnostic| core::cmp::Reverse(diagnostic.severity()));
+        diagnostics
+    }
+
+    fn dt_start_sort_key(&self) -> chrono::NaiveDateTime {
+        let dt_start = self.dt_start.0.to_datetime();
+
+        if self.dt_start.0.result_tz().is_some() {
+            dt_start.naive_utc()
+        } else {
+            dt_start.naive_local()
+        }
+    }
+
+    /// Check if any month of BYMONTH has any day of BYMONTHDAY, considering leap years
+    pub(crate) fn month_days_exist(&self) -> bool {
+        let recur = &self.recur;
+
+        if recur.by_month.is_empty() || recur.by_month_day.is_empty() {
+            return true;
+        }
+
+        recur.by_month.iter().any(|&month1| {
+            let days = match mappings::days_in_month(true, month1.wrapping_sub(1)) {
+                Some(days) => days as i32,
+                None => return true,
+            };
+
+            recur.by_month_day.iter().any(|&day| day.abs() <= days)
+        })
+    }
+
+    /// Month days over 28 of a MONTHLY rule which do not exist in some months
+    fn skipped_month_days(&self) -> Option<Vec<i32>> {
+        let recur = &self.recur;
+
+        if recur.freq != Frequency::Monthly || !recur.by_set_pos.is_empty() {
+            return None;
+        }
+
+        let month_days = if !recur.by_month_day.is_empty() {
+            recur.by_month_day.clone()
+        } else if recur.by_day.is_empty() {
+            // Implied by DTSTART
+            vec![self.dt_start.0.to_datetime().day() as i32]
+        } else {
+            return None;
+        };
+
+        // With any of the first 28 days every month has an occurrence
+        if month_days.iter().any(|&day| day.abs() <= 28) {
+            return None;
+        }
+
+        let short_month = |month1: u32| {
+            mappings::days_in_month(false, month1 - 1)
+                .is_some_and(|days| month_days.iter().all(|&day| day.unsigned_abs() > days))
+        };
+
+        let months: Vec<u32> = if recur.by_month.is_empty() {
+            (1..=12).collect()
+        } else {
+            recur.by_month.clone()
+        };
+
+        if recur
+            .by_day
+            .iter()
+            .any(|by_day| matches!(by_day, ByDay::Nth(..)))
+            || !months
+                .iter()
+                .any(|&month| (1..=12).contains(&month) && short_month(month))
+        {
+            return None;
+        }
+
+        Some(month_days)
+    }
+}
+
+/// Approximate number of years COUNT occurrences span at least
+fn span_years(freq: Frequency, interval: u32, count: u32) -> u64 {
+    let seconds = match freq {
+        Frequency::Secondly => 1,
+        Frequency::Minutely => 60,
+        Frequency::Hourly => 3600,
+        Frequency::Daily => 86400,
+        Frequency::Weekly => 7 * 86400,
+        Frequency::Monthly => 2_629_746,
+        Frequency::Yearly => 31_556_952,
+    };
+
+    u64::from(interval) * u64::from(count.saturating_sub(1)) * seconds / 31_556_952
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(s: &str) -> Vec<&'static str> {
+        s.parse::<RRule>()
+            .unwrap()
+            .lint()
+            .iter()
+            .map(|diagnostic| diagnostic.code.code())
+            .collect()
+    }
+
+    #[test]
+    fn lint_codes() {
+        assert!(lint("DTSTART:20220103T090000\nRRULE:FREQ=WEEKLY;BYDAY=MO").is_empty());
+
+        assert_eq!(
+            lint("DTSTART:20220103T090000\nRRULE:FREQ=WEEKLY;BYDAY=TU"),
+            ["W001"]
+        );
+        assert_eq!(
+            lint("DTSTART:20220131T090000\nRRULE:FREQ=MONTHLY"),
+            ["W002"]
+        );
+        assert_eq!(
+            lint("DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;INTERVAL=50;COUNT=10"),
+            ["W003"]
+        );
+        assert_eq!(
+            lint("DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30"),
+            ["E009"]
+        );
+        assert_eq!(
+            lint("DTSTART:20220103T090000\nRRULE:FREQ=WEEKLY;INTERVAL=0;BYDAY=1MO;BYMONTHDAY=1"),
+            ["E001", "E008", "E008"]
+        );
+        assert_eq!(
+            lint("DTSTART:20220103T090000\nRRULE:FREQ=DAILY;BYHOUR=25;BYMINUTE=60"),
+            ["E007", "E007"]
+        );
+    }
+
+    #[test]
+    fn display() {
+        let rrule: RRule = "DTSTART:20220131T090000\nRRULE:FREQ=MONTHLY;BYMONTHDAY=31"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            rrule.lint()[0].to_string(),
+            "warning[W002]: months shorter than 31 days are skipped"
+        );
+
+        let rrule: RRule = "DTSTART:20220131T090000\nRRULE:FREQ=MONTHLY;BYMONTHDAY=-31"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            rrule.lint()[0].to_string(),
+            "warning[W002]: months shorter than 31 days are skipped"
+        );
+    }
+}
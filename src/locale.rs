@@ -0,0 +1,125 @@
+use crate::byday::ByDay;
+use crate::freq::Frequency;
+use crate::weekday::Weekday;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use chrono::{NaiveDateTime, NaiveTime, Timelike};
+
+mod de;
+mod en;
+
+pub use de::German;
+pub use en::English;
+
+/// CLDR plural categories, languages only need to return the ones they distinguish
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+/// Named sets of weekdays, which most languages have a single word for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DaySet {
+    Single(Weekday),
+    Weekdays,
+    Weekend,
+    All,
+}
+
+/// Language pack used to render rule descriptions, see [`RRule::describe_with`](crate::rrule::RRule::describe_with)
+///
+/// The renderer decides which parts of a rule are described and in which order,
+/// implementations supply the words and phrases for each part.
+/// Phrases are returned without leading or trailing whitespace.
+///
+/// Numbers passed as ordinals may be negative, counting from the end (-1 = last).
+pub trait Locale {
+    // == Vocabulary ==
+
+    fn weekday(&self, weekday: Weekday) -> &str;
+
+    /// Name of the month, `month1` ranges from 1 to 12
+    fn month(&self, month1: u32) -> &str;
+
+    fn ordinal(&self, n: i32) -> String;
+
+    fn plural(&self, n: u32) -> PluralCategory;
+
+    /// Join items into a list, e.g. "A, B and C"
+    fn join(&self, items: Vec<String>) -> String;
+
+    fn time(&self, time: NaiveTime) -> String {
+        if time.second() == 0 {
+            time.format("%H:%M").to_string()
+        } else {
+            time.format("%H:%M:%S").to_string()
+        }
+    }
+
+    // == Phrases ==
+
+    /// Beginning of the sentence, e.g. "Weekly" or "Every 2 weeks"
+    fn frequency(&self, freq: Frequency, interval: u32) -> String;
+
+    /// Restriction of a sub-daily rule to the given hours, minutes or seconds (`unit`),
+    /// e.g. "during hours 9 and 17"
+    fn during(&self, unit: Frequency, values: &[u32]) -> String;
+
+    /// Hours, minutes or seconds (`unit`) an occurrence of a sub-daily rule is set to,
+    /// e.g. "at minute 30"
+    fn at(&self, unit: Frequency, values: &[u32]) -> String;
+
+    /// e.g. "on Monday and the last Friday", with "of the year" if `of_year` is set
+    fn on_days(&self, days: &[ByDay], of_year: bool) -> String;
+
+    /// e.g. "on weekdays"
+    fn on_day_set(&self, set: DaySet) -> String;
+
+    /// BYSETPOS applied to a [`DaySet`], e.g. "on the last weekday"
+    fn nth_of_day_set(&self, nth: &[i32], set: DaySet) -> String;
+
+    /// e.g. "on the 1st and 15th", with "day of the month" if `of_month` is set
+    fn on_month_days(&self, days: &[i32], of_month: bool) -> String;
+
+    /// Month days following a weekday phrase, e.g. "the 13th" in "on Friday the 13th"
+    fn weekday_month_days(&self, days: &[i32]) -> String;
+
+    /// e.g. "on the 100th day of the year"
+    fn on_year_days(&self, days: &[i32]) -> String;
+
+    /// e.g. "in the 1st and last week of the year"
+    fn in_weeks(&self, weeks: &[i32]) -> String;
+
+    /// e.g. "in January and July"
+    fn in_months(&self, months: &[u32]) -> String;
+
+    /// e.g. "at 09:00 and 17:00"
+    fn at_times(&self, times: &[NaiveTime]) -> String;
+
+    /// General BYSETPOS, e.g. "only the 2nd occurrence in each month"
+    fn set_pos(&self, positions: &[i32], freq: Frequency) -> String;
+
+    /// e.g. "10 times"
+    fn count(&self, count: u32) -> String;
+
+    /// e.g. "until Dec 31, 2025", the time is only relevant if `with_time` is set
+    fn until(&self, until: NaiveDateTime, with_time: bool) -> String;
+}
+
+/// Join `items` with `separator` and the last two items with `last_separator`
+pub fn join_with(mut items: Vec<String>, separator: &str, last_separator: &str) -> String {
+    match items.len() {
+        0 => String::new(),
+        1 => items.remove(0),
+        _ => {
+            let last = items.pop().unwrap();
+            format!("{}{}{}", items.join(separator), last_separator, last)
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use super::{join_with, DaySet, Locale, PluralCategory};
+use crate::byday::ByDay;
+use crate::freq::Frequency;
+use crate::weekday::Weekday;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use chrono::{NaiveDateTime, NaiveTime};
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct German;
+
+impl German {
+    fn unit(&self, unit: Frequency, n: u32) -> &'static str {
+        let one = self.plural(n) == PluralCategory::One;
+
+        match (unit, one) {
+            (Frequency::Secondly, true) => "Sekunde",
+            (Frequency::Secondly, false) => "Sekunden",
+            (Frequency::Minutely, true) => "Minute",
+            (Frequency::Minutely, false) => "Minuten",
+            (Frequency::Hourly, true) => "Stunde",
+            (Frequency::Hourly, false) => "Stunden",
+            (Frequency::Daily, true) => "Tag",
+            (Frequency::Daily, false) => "Tage",
+            (Frequency::Weekly, true) => "Woche",
+            (Frequency::Weekly, false) => "Wochen",
+            (Frequency::Monthly, true) => "Monat",
+            (Frequency::Monthly, false) => "Monate",
+            (Frequency::Yearly, true) => "Jahr",
+            (Frequency::Yearly, false) => "Jahre",
+        }
+    }
+
+    fn numbers(&self, values: &[u32]) -> String {
+        self.join(values.iter().map(u32::to_string).collect())
+    }
+
+    fn ordinals(&self, values: &[i32]) -> String {
+        self.join(values.iter().map(|&n| self.ordinal(n)).collect())
+    }
+}
+
+impl Locale for German {
+    fn weekday(&self, weekday: Weekday) -> &str {
+        match weekday {
+            Weekday::Monday => "Montag",
+            Weekday::Tuesday => "Dienstag",
+            Weekday::Wednesday => "Mittwoch",
+            Weekday::Thursday => "Donnerstag",
+            Weekday::Friday => "Freitag",
+            Weekday::Saturday => "Samstag",
+            Weekday::Sunday => "Sonntag",
+        }
+    }
+
+    fn month(&self, month1: u32) -> &str {
+        match month1 {
+            1 => "Januar",
+            2 => "Februar",
+            3 => "März",
+            4 => "April",
+            5 => "Mai",
+            6 => "Juni",
+            7 => "Juli",
+            8 => "August",
+            9 => "September",
+            10 => "Oktober",
+            11 => "November",
+            12 => "Dezember",
+            _ => "?",
+        }
+    }
+
+    /// Dative ordinals as used in "am letzten Freitag": 1 => 1., -1 => letzten, -2 => vorletzten
+    fn ordinal(&self, n: i32) -> String {
+        match n {
+            -1 => "letzten".into(),
+            -2 => "vorletzten".into(),
+            n if n < 0 => format!("{}.-letzten", -n),
+            n => format!("{}.", n),
+        }
+    }
+
+    fn plural(&self, n: u32) -> PluralCategory {
+        if n == 1 {
+            PluralCategory::One
+        } else {
+            PluralCategory::Other
+        }
+    }
+
+    fn join(&self, items: Vec<String>) -> String {
+        join_with(items, ", ", " und ")
+    }
+
+    fn frequency(&self, freq: Frequency, interval: u32) -> String {
+        if interval == 1 {
+            return match freq {
+                Frequency::Secondly => "Jede Sekunde",
+                Frequency::Minutely => "Jede Minute",
+                Frequency::Hourly => "Stündlich",
+                Frequency::Daily => "Täglich",
+                Frequency::Weekly => "Wöchentlich",
+                Frequency::Monthly => "Monatlich",
+                Frequency::Yearly => "Jährlich",
+            }
+            .into();
+        }
+
+        format!("Alle {} {}", interval, self.unit(freq, interval))
+    }
+
+    fn during(&self, unit: Frequency, values: &[u32]) -> String {
+        let n = values.len() as u32;
+
+        if self.plural(n) == PluralCategory::One {
+            format!("in {} {}", self.unit(unit, n), self.numbers(values))
+        } else {
+            format!("in den {} {}", self.unit(unit, n), self.numbers(values))
+        }
+    }
+
+    fn at(&self, unit: Frequency, values: &[u32]) -> String {
+        let n = values.len() as u32;
+
+        if self.plural(n) == PluralCategory::One {
+            format!("zur {} {}", self.unit(unit, n), self.numbers(values))
+        } else {
+            format!("zu den {} {}", self.unit(unit, n), self.numbers(values))
+        }
+    }
+
+    fn on_days(&self, days: &[ByDay], of_year: bool) -> String {
+        let days = days
+            .iter()
+            .map(|by_day| match *by_day {
+                ByDay::All(weekday) => self.weekday(weekday).into(),
+                ByDay::Nth(weekday, nth) => {
+                    format!("{} {}", self.ordinal(nth), self.weekday(weekday))
+                }
+            })
+            .collect();
+
+        if of_year {
+            format!("am {} des Jahres", self.join(days))
+        } else {
+            format!("am {}", self.join(days))
+        }
+    }
+
+    fn on_day_set(&self, set: DaySet) -> String {
+        match set {
+            DaySet::Single(weekday) => format!("am {}", self.weekday(weekday)),
+            DaySet::Weekdays => "werktags".into(),
+            DaySet::Weekend => "am Wochenende".into(),
+            DaySet::All => "jeden Tag".into(),
+        }
+    }
+
+    fn nth_of_day_set(&self, nth: &[i32], set: DaySet) -> String {
+        let set = match set {
+            DaySet::Single(weekday) => self.weekday(weekday),
+            DaySet::Weekdays => "Werktag",
+            DaySet::Weekend => "Wochenendtag",
+            DaySet::All => "Tag",
+        };
+
+        format!("am {} {}", self.ordinals(nth), set)
+    }
+
+    fn on_month_days(&self, days: &[i32], of_month: bool) -> String {
+        if of_month {
+            format!("am {} Tag des Monats", self.ordinals(days))
+        } else {
+            format!("am {}", self.ordinals(days))
+        }
+    }
+
+    fn weekday_month_days(&self, days: &[i32]) -> String {
+        format!("den {}", self.ordinals(days))
+    }
+
+    fn on_year_days(&self, days: &[i32]) -> String {
+        format!("am {} Tag des Jahres", self.ordinals(days))
+    }
+
+    fn in_weeks(&self, weeks: &[i32]) -> String {
+        format!("in der {} Woche des Jahres", self.ordinals(weeks))
+    }
+
+    fn in_months(&self, months: &[u32]) -> String {
+        let months = months.iter().map(|&m| self.month(m).into()).collect();
+
+        format!("im {}", self.join(months))
+    }
+
+    fn at_times(&self, times: &[NaiveTime]) -> String {
+        let times = times.iter().map(|&time| self.time(time)).collect();
+
+        format!("um {} Uhr", self.join(times))
+    }
+
+    fn set_pos(&self, positions: &[i32], freq: Frequency) -> String {
+        let each = match freq {
+            Frequency::Secondly => "jeder Sekunde",
+            Frequency::Minutely => "jeder Minute",
+            Frequency::Hourly => "jeder Stunde",
+            Frequency::Daily => "jedes Tages",
+            Frequency::Weekly => "jeder Woche",
+            Frequency::Monthly => "jedes Monats",
+            Frequency::Yearly => "jedes Jahres",
+        };
+
+        format!(
+            "jeweils nur am {} Termin {}",
+            self.ordinals(positions),
+            each
+        )
+    }
+
+    fn count(&self, count: u32) -> String {
+        if count == 1 {
+            "einmal".into()
+        } else {
+            format!("{} Mal", count)
+        }
+    }
+
+    fn until(&self, until: NaiveDateTime, with_time: bool) -> String {
+        if with_time {
+            format!(
+                "bis {} {} Uhr",
+                until.format("%d.%m.%Y"),
+                self.time(until.time())
+            )
+        } else {
+            format!("bis {}", until.format("%d.%m.%Y"))
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use super::{join_with, DaySet, Locale, PluralCategory};
+use crate::byday::ByDay;
+use crate::freq::Frequency;
+use crate::weekday::Weekday;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use chrono::{NaiveDateTime, NaiveTime};
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct English;
+
+impl English {
+    fn unit(&self, unit: Frequency, n: u32) -> &'static str {
+        let one = self.plural(n) == PluralCategory::One;
+
+        match (unit, one) {
+            (Frequency::Secondly, true) => "second",
+            (Frequency::Secondly, false) => "seconds",
+            (Frequency::Minutely, true) => "minute",
+            (Frequency::Minutely, false) => "minutes",
+            (Frequency::Hourly, true) => "hour",
+            (Frequency::Hourly, false) => "hours",
+            (Frequency::Daily, true) => "day",
+            (Frequency::Daily, false) => "days",
+            (Frequency::Weekly, true) => "week",
+            (Frequency::Weekly, false) => "weeks",
+            (Frequency::Monthly, true) => "month",
+            (Frequency::Monthly, false) => "months",
+            (Frequency::Yearly, true) => "year",
+            (Frequency::Yearly, false) => "years",
+        }
+    }
+
+    fn numbers(&self, values: &[u32]) -> String {
+        self.join(values.iter().map(u32::to_string).collect())
+    }
+
+    fn ordinals(&self, values: &[i32]) -> String {
+        self.join(values.iter().map(|&n| self.ordinal(n)).collect())
+    }
+}
+
+impl Locale for English {
+    fn weekday(&self, weekday: Weekday) -> &str {
+        match weekday {
+            Weekday::Monday => "Monday",
+            Weekday::Tuesday => "Tuesday",
+            Weekday::Wednesday => "Wednesday",
+            Weekday::Thursday => "Thursday",
+            Weekday::Friday => "Friday",
+            Weekday::Saturday => "Saturday",
+            Weekday::Sunday => "Sunday",
+        }
+    }
+
+    fn month(&self, month1: u32) -> &str {
+        match month1 {
+            1 => "January",
+            2 => "February",
+            3 => "March",
+            4 => "April",
+            5 => "May",
+            6 => "June",
+            7 => "July",
+            8 => "August",
+            9 => "September",
+            10 => "October",
+            11 => "November",
+            12 => "December",
+            _ => "?",
+        }
+    }
+
+    /// 1 => 1st, 2 => 2nd, -1 => last, -2 => 2nd to last
+    fn ordinal(&self, n: i32) -> String {
+        match n {
+            -1 => "last".into(),
+            n if n < 0 => format!("{} to last", self.ordinal(-n)),
+            n => {
+                let suffix = match (n % 10, n % 100) {
+                    (_, 11..=13) => "th",
+                    (1, _) => "st",
+                    (2, _) => "nd",
+                    (3, _) => "rd",
+                    _ => "th",
+                };
+
+                format!("{}{}", n, suffix)
+            }
+        }
+    }
+
+    fn plural(&self, n: u32) -> PluralCategory {
+        if n == 1 {
+            PluralCategory::One
+        } else {
+            PluralCategory::Other
+        }
+    }
+
+    fn join(&self, items: Vec<String>) -> String {
+        join_with(items, ", ", " and ")
+    }
+
+    fn frequency(&self, freq: Frequency, interval: u32) -> String {
+        if interval == 1 {
+            return match freq {
+                Frequency::Secondly => "Every second",
+                Frequency::Minutely => "Every minute",
+                Frequency::Hourly => "Hourly",
+                Frequency::Daily => "Daily",
+                Frequency::Weekly => "Weekly",
+                Frequency::Monthly => "Monthly",
+                Frequency::Yearly => "Yearly",
+            }
+            .into();
+        }
+
+        format!("Every {} {}", interval, self.unit(freq, interval))
+    }
+
+    fn during(&self, unit: Frequency, values: &[u32]) -> String {
+        format!(
+            "during {} {}",
+            self.unit(unit, values.len() as u32),
+            self.numbers(values)
+        )
+    }
+
+    fn at(&self, unit: Frequency, values: &[u32]) -> String {
+        format!(
+            "at {} {}",
+            self.unit(unit, values.len() as u32),
+            self.numbers(values)
+        )
+    }
+
+    fn on_days(&self, days: &[ByDay], of_year: bool) -> String {
+        let days = days
+            .iter()
+            .map(|by_day| match *by_day {
+                ByDay::All(weekday) => self.weekday(weekday).into(),
+                ByDay::Nth(weekday, nth) => {
+                    format!("the {} {}", self.ordinal(nth), self.weekday(weekday))
+                }
+            })
+            .collect();
+
+        if of_year {
+            format!("on {} of the year", self.join(days))
+        } else {
+            format!("on {}", self.join(days))
+        }
+    }
+
+    fn on_day_set(&self, set: DaySet) -> String {
+        match set {
+            DaySet::Single(weekday) => format!("on {}", self.weekday(weekday)),
+            DaySet::Weekdays => "on weekdays".into(),
+            DaySet::Weekend => "on weekends".into(),
+            DaySet::All => "every day".into(),
+        }
+    }
+
+    fn nth_of_day_set(&self, nth: &[i32], set: DaySet) -> String {
+        let set = match set {
+            DaySet::Single(weekday) => self.weekday(weekday),
+            DaySet::Weekdays => "weekday",
+            DaySet::Weekend => "weekend day",
+            DaySet::All => "day",
+        };
+
+        format!("on the {} {}", self.ordinals(nth), set)
+    }
+
+    fn on_month_days(&self, days: &[i32], of_month: bool) -> String {
+        if of_month {
+            format!("on the {} day of the month", self.ordinals(days))
+        } else {
+            format!("on the {}", self.ordinals(days))
+        }
+    }
+
+    fn weekday_month_days(&self, days: &[i32]) -> String {
+        format!("the {}", self.ordinals(days))
+    }
+
+    fn on_year_days(&self, days: &[i32]) -> String {
+        format!("on the {} day of the year", self.ordinals(days))
+    }
+
+    fn in_weeks(&self, weeks: &[i32]) -> String {
+        format!("in the {} week of the year", self.ordinals(weeks))
+    }
+
+    fn in_months(&self, months: &[u32]) -> String {
+        let months = months.iter().map(|&m| self.month(m).into()).collect();
+
+        format!("in {}", self.join(months))
+    }
+
+    fn at_times(&self, times: &[NaiveTime]) -> String {
+        let times = times.iter().map(|&time| self.time(time)).collect();
+
+        format!("at {}", self.join(times))
+    }
+
+    fn set_pos(&self, positions: &[i32], freq: Frequency) -> String {
+        format!(
+            "only the {} occurrence in each {}",
+            self.ordinals(positions),
+            self.unit(freq, 1)
+        )
+    }
+
+    fn count(&self, count: u32) -> String {
+        if count == 1 {
+            "once".into()
+        } else {
+            format!("{} times", count)
+        }
+    }
+
+    fn until(&self, until: NaiveDateTime, with_time: bool) -> String {
+        if with_time {
+            format!(
+                "until {} {}",
+                until.format("%b %-d, %Y"),
+                self.time(until.time())
+            )
+        } else {
+            format!("until {}", until.format("%b %-d, %Y"))
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use rruler::iter::RRuleIterYield;
+use rruler::lint::Severity;
+use rruler::rrule::RRule;
+use rruler::rrule_set::RRuleSet;
+use std::io::{self, BufWriter, IsTerminal, Read, Write};
+use std::process::ExitCode;
+use std::str::FromStr;
+
+const USAGE: &str = "\
+Expand the occurrences of a recurrence rule
+
+Usage: rruler [OPTIONS] [RULE]
+       rruler lint [OPTIONS] [RULE]
+
+Reads a DTSTART/RRULE block (optionally with EXRULE, RDATE and EXDATE lines)
+from RULE, from the file given with --file, or from stdin. Lines of RULE may
+be separated by whitespace instead of line breaks.
+
+`lint` reports every problem of the rules instead of expanding them and exits
+with 1 if any of them is an error. Only --file and --format apply.
+
+Options:
+  -f, --file <PATH>          Read the rule from a file, `-` for stdin
+  -n, --count <N>            Print at most N occurrences (default 10 for unbounded rules)
+      --after <DATETIME>     Only print occurrences after DATETIME
+      --between <START> <END>
+                             Only print occurrences between START and END, inclusive
+      --tz <TZ>              Print occurrences in timezone TZ, floating rules are placed in TZ
+  -o, --format <FORMAT>      Output format: basic (RFC 5545), iso (ISO 8601), json (JSON lines),
+                             lint supports basic and json
+  -h, --help                 Print this help
+
+DATETIME is given as 2022-01-03T09:00:00, 20220103T090000 or a date, in the
+timezone of the output.
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Basic,
+    Iso,
+    Json,
+}
+
+enum Input {
+    Rule(String),
+    File(String),
+    Stdin,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Expand,
+    Lint,
+}
+
+struct Options {
+    command: Command,
+    input: Input,
+    count: Option<usize>,
+    after: Option<NaiveDateTime>,
+    between: Option<(NaiveDateTime, NaiveDateTime)>,
+    tz: Option<Tz>,
+    format: Format,
+}
+
+/// Reasons to stop early, usage errors exit with 2, all others with 1
+enum Error {
+    Help,
+    Usage(String),
+    Other(String),
+}
+
+fn parse_datetime(s: &str) -> Result<NaiveDateTime, Error> {
+    ["%Y-%m-%dT%H:%M:%S", "%Y%m%dT%H%M%S", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+        .or_else(|| {
+            ["%Y-%m-%d", "%Y%m%d"]
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(s, format).ok())
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(|| Error::Usage(format!("invalid datetime '{}'", s)))
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Options, Error> {
+    let mut args = args.peekable();
+
+    let command = if args.next_if(|arg| arg == "lint").is_some() {
+        Command::Lint
+    } else {
+        Command::Expand
+    };
+
+    let mut options = Options {
+        command,
+        input: Input::Stdin,
+        count: None,
+        after: None,
+        between: None,
+        tz: None,
+        format: Format::Basic,
+    };
+
+    let mut rule = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| Error::Usage(format!("missing value for {}", name)))
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => return Err(Error::Help),
+            "-f" | "--file" => {
+                options.input = match value(&arg)?.as_str() {
+                    "-" => Input::Stdin,
+                    path => Input::File(path.into()),
+                }
+            }
+            "-n" | "--count" => {
+                let count = value(&arg)?;
+
+                options.count = Some(
+                    usize::from_str(&count)
+                        .map_err(|_| Error::Usage(format!("invalid count '{}'", count)))?,
+                );
+            }
+            "--after" => options.after = Some(parse_datetime(&value(&arg)?)?),
+            "--between" => {
+                let start = parse_datetime(&value(&arg)?)?;
+                let end = parse_datetime(&value(&arg)?)?;
+
+                options.between = Some((start, end));
+            }
+            "--tz" => {
+                let tz = value(&arg)?;
+
+                options.tz = Some(
+                    Tz::from_str(&tz)
+                        .map_err(|_| Error::Usage(format!("unknown timezone '{}'", tz)))?,
+                );
+            }
+            "-o" | "--format" => {
+                options.format = match value(&arg)?.as_str() {
+                    "basic" => Format::Basic,
+                    "iso" => Format::Iso,
+                    "json" => Format::Json,
+                    format => return Err(Error::Usage(format!("unknown format '{}'", format))),
+                }
+            }
+            _ if arg.starts_with('-') && arg != "-" => {
+                return Err(Error::Usage(format!("unknown option '{}'", arg)))
+            }
+            _ => {
+                if rule.replace(arg).is_some() {
+                    return Err(Error::Usage("only a single RULE may be given".into()));
+                }
+            }
+        }
+    }
+
+    if let Some(rule) = rule {
+        if rule != "-" {
+            options.input = Input::Rule(rule);
+        }
+    }
+
+    Ok(options)
+}
+
+fn read_input(input: &Input) -> Result<String, Error> {
+    let input = match input {
+        Input::Rule(rule) => rule.clone(),
+        Input::File(path) => std::fs::read_to_string(path)
+            .map_err(|e| Error::Other(format!("failed to read '{}': {}", path, e)))?,
+        Input::Stdin => {
+            let mut input = String::new();
+
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| Error::Other(format!("failed to read stdin: {}", e)))?;
+
+            input
+        }
+    };
+
+    // Content lines never contain whitespace, which allows passing rules in a single line
+    Ok(input.split_whitespace().collect::<Vec<_>>().join("\n"))
+}
+
+enum Occurrence {
+    Floating(NaiveDateTime),
+    Tz(DateTime<Tz>),
+}
+
+impl Occurrence {
+    fn new(yielded: RRuleIterYield, tz: Option<Tz>) -> Self {
+        match (yielded, tz) {
+            (RRuleIterYield::DateTimeLocal(datetime), None) => Self::Floating(datetime),
+            (RRuleIterYield::DateTimeLocal(datetime), Some(tz)) => Self::Tz(
+                tz.from_local_datetime(&datetime)
+                    .earliest()
+                    .unwrap_or_else(|| tz.from_utc_datetime(&datetime)),
+            ),
+            (RRuleIterYield::DateTimeTz(datetime), None) => Self::Tz(datetime),
+            (RRuleIterYield::DateTimeTz(datetime), Some(tz)) => {
+                Self::Tz(datetime.with_timezone(&tz))
+            }
+        }
+    }
+
+    fn local(&self) -> NaiveDateTime {
+        match self {
+            Self::Floating(datetime) => *datetime,
+            Self::Tz(datetime) => datetime.naive_local(),
+        }
+    }
+
+    fn format(&self, format: Format) -> String {
+        match (self, format) {
+            (Self::Floating(datetime), Format::Basic) => {
+                datetime.format("%Y%m%dT%H%M%S").to_string()
+            }
+            (Self::Tz(datetime), Format::Basic) if datetime.timezone() == Tz::UTC => {
+                datetime.format("%Y%m%dT%H%M%SZ").to_string()
+            }
+            (Self::Tz(datetime), Format::Basic) => datetime
+                .format(&format!(
+                    "TZID={}:%Y%m%dT%H%M%S",
+                    datetime.timezone().name()
+                ))
+                .to_string(),
+            (Self::Floating(datetime), Format::Iso) => {
+                datetime.format("%Y-%m-%dT%H:%M:%S").to_string()
+            }
+            (Self::Tz(datetime), Format::Iso) => {
+                datetime.format("%Y-%m-%dT%H:%M:%S%:z").to_string()
+            }
+            (Self::Floating(datetime), Format::Json) => serde_json::json!({
+                "start": datetime.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                "timeZone": null,
+            })
+            .to_string(),
+            (Self::Tz(datetime), Format::Json) => serde_json::json!({
+                "start": datetime.naive_local().format("%Y-%m-%dT%H:%M:%S").to_string(),
+                "timeZone": datetime.timezone().name(),
+                "utc": datetime.naive_utc().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            })
+            .to_string(),
+        }
+    }
+}
+
+fn run(options: Options) -> Result<(), Error> {
+    let input = read_input(&options.input)?;
+
+    let set = RRuleSet::from_str(&input)
+        .map_err(|e| Error::Other(format!("invalid rule: {}", e.to_string().trim_end())))?;
+
+    if options.command == Command::Lint {
+        return lint(&set, options.format);
+    }
+
+    for recur in set.rrules.iter().chain(&set.exrules) {
+        RRule::new(set.dt_start, recur.clone())
+            .verify(false)
+            .map_err(|e| Error::Other(format!("invalid rule: {}", e)))?;
+    }
+
+    let unbounded = set
+        .rrules
+        .iter()
+        .any(|recur| recur.count.is_none() && recur.until.is_none());
+
+    let count = match options.count {
+        Some(count) => count,
+        None if unbounded && options.between.is_none() => 10,
+        None => usize::MAX,
+    };
+
+    let stdout = io::stdout();
+    // Show occurrences as they are found on a terminal, slow rules may take a while for the next
+    let interactive = stdout.is_terminal();
+    let mut out = BufWriter::new(stdout.lock());
+
+    let occurrences = set
+        .iter()
+        .map(|yielded| Occurrence::new(yielded, options.tz))
+        .filter(|occurrence| options.after.is_none_or(|after| occurrence.local() > after))
+        .skip_while(|occurrence| {
+            options
+                .between
+                .is_some_and(|(start, _)| occurrence.local() < start)
+        })
+        .take_while(|occurrence| {
+            options
+                .between
+                .is_none_or(|(_, end)| occurrence.local() <= end)
+        })
+        .take(count);
+
+    for occurrence in occurrences {
+        writeln!(out, "{}", occurrence.format(options.format))
+            .and_then(|_| if interactive { out.flush() } else { Ok(()) })
+            .map_err(|e| Error::Other(format!("failed to write output: {}", e)))?;
+    }
+
+    out.flush()
+        .map_err(|e| Error::Other(format!("failed to write output: {}", e)))
+}
+
+fn lint(set: &RRuleSet, format: Format) -> Result<(), Error> {
+    let rules = set
+        .rrules
+        .iter()
+        .map(|recur| ("RRULE", recur))
+        .chain(set.exrules.iter().map(|recur| ("EXRULE", recur)));
+
+    let mut errors = 0;
+
+    for (i, (name, recur)) in rules.enumerate() {
+        for diagnostic in RRule::new(set.dt_start, recur.clone()).lint() {
+            if diagnostic.severity() == Severity::Error {
+                errors += 1;
+            }
+
+            if format == Format::Json {
+                let severity = match diagnostic.severity() {
+                    Severity::Warning => "warning",
+                    Severity::Error => "error",
+                };
+
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "rule": i,
+                        "property": name,
+                        "code": diagnostic.code.code(),
+                        "severity": severity,
+                        "message": diagnostic.message,
+                    })
+                );
+            } else {
+                println!("{}:{}: {}", name, recur, diagnostic);
+            }
+        }
+    }
+
+    if errors > 0 {
+        return Err(Error::Other(format!("found {} error(s)", errors)));
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = parse_args(std::env::args().skip(1)).and_then(run);
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Error::Help) => {
+            print!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+        Err(Error::Usage(message)) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            ExitCode::from(2)
+        }
+        Err(Error::Other(message)) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::Range;
+use core::ops::Range;
 
 #[rustfmt::skip]
 pub static YEARDAY_TO_MONTH_NORMAL: [u8; 365] = [
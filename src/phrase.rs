@@ -0,0 +1,900 @@
+use crate::byday::ByDay;
+use crate::dt::Dt;
+use crate::dt_prop::{local_datetime_with_tz, DtProperty, DtStart};
+use crate::freq::Frequency;
+use crate::recur::Recur;
+use crate::rrule::{RRule, RRuleVerifyError};
+use crate::weekday::Weekday;
+use crate::zone::DefaultZone;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Timelike, Utc};
+use core::ops::Range;
+
+const WEEKDAYS: [Weekday; 5] = [
+    Weekday::Monday,
+    Weekday::Tuesday,
+    Weekday::Wednesday,
+    Weekday::Thursday,
+    Weekday::Friday,
+];
+
+const WEEKEND: [Weekday; 2] = [Weekday::Saturday, Weekday::Sunday];
+
+/// Recurrence parsed from an english phrase like "every other Friday at 9am until June"
+#[derive(Debug, Clone)]
+pub struct Phrase {
+    /// The parsed rule, UNTIL is always a DATE and retyped by [`Phrase::to_rrule`]
+    pub recur: Recur,
+    /// Time of day for DTSTART, if the phrase mentioned one
+    pub time: Option<NaiveTime>,
+    /// Parts of the input which were ignored
+    pub unrecognized: Vec<Unrecognized>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unrecognized {
+    /// Byte range inside the input
+    pub span: Range<usize>,
+    pub text: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PhraseError {
+    #[error("could not find a frequency in the phrase")]
+    NoFrequency,
+}
+
+impl Phrase {
+    /// Parse an english recurrence phrase
+    ///
+    /// `today` is used to resolve dates without a year, e.g. "until June".
+    pub fn parse(input: &str, today: NaiveDate) -> Result<Self, PhraseError> {
+        let tokens = tokenize(input);
+
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            today,
+            freq: None,
+            interval: None,
+            count: None,
+            until: None,
+            by_day: vec![],
+            by_month_day: vec![],
+            by_year_day: vec![],
+            by_month: vec![],
+            by_set_pos: vec![],
+            yearly_scope: false,
+            times: vec![],
+            times_span: None,
+            unrecognized: vec![],
+        };
+
+        parser.run();
+
+        let mut unrecognized: Vec<Unrecognized> = vec![];
+
+        for span in parser.unrecognized {
+            // merge adjacent fragments
+            match unrecognized.last_mut() {
+                Some(last) if input[last.span.end..span.start].trim().is_empty() => {
+                    last.span.end = span.end;
+                }
+                _ => unrecognized.push(Unrecognized {
+                    span: span.clone(),
+                    text: String::new(),
+                }),
+            }
+        }
+
+        for fragment in &mut unrecognized {
+            fragment.text = input[fragment.span.clone()].into();
+        }
+
+        let has_nth = parser
+            .by_day
+            .iter()
+            .any(|by_day| matches!(by_day, ByDay::Nth(..)));
+
+        let freq = match parser.freq {
+            Some(freq) => freq,
+            None if parser.yearly_scope => Frequency::Yearly,
+            None if has_nth || !parser.by_month_day.is_empty() || !parser.by_set_pos.is_empty() => {
+                Frequency::Monthly
+            }
+            None if !parser.by_day.is_empty() => Frequency::Weekly,
+            None if !parser.by_month.is_empty() => Frequency::Yearly,
+            None if !parser.times.is_empty() => Frequency::Daily,
+            None => return Err(PhraseError::NoFrequency),
+        };
+
+        let mut recur = Recur::new(freq);
+        recur.interval = parser.interval;
+        recur.count = parser.count;
+        recur.until = parser.until.map(Dt::Date);
+        recur.by_day = parser.by_day;
+        recur.by_month_day = parser.by_month_day;
+        recur.by_year_day = parser.by_year_day;
+        recur.by_month = parser.by_month;
+        recur.by_set_pos = parser.by_set_pos;
+        recur.sort_and_dedup();
+
+        let mut times = parser.times;
+        times.sort_unstable();
+        times.dedup();
+
+        let time = match times.as_slice() {
+            [] => None,
+            [time] => Some(*time),
+            [first, ..] => {
+                let mut hours: Vec<u32> = times.iter().map(|t| t.hour()).collect();
+                let mut minutes: Vec<u32> = times.iter().map(|t| t.minute()).collect();
+                hours.dedup();
+                minutes.sort_unstable();
+                minutes.dedup();
+
+                // only expressible if the times are the product of BYHOUR and BYMINUTE
+                if hours.len() * minutes.len() == times.len() {
+                    recur.by_hour = hours;
+                    recur.by_minute = minutes;
+                    recur.sort_and_dedup();
+                } else if let Some(span) = parser.times_span {
+                    unrecognized.push(Unrecognized {
+                        text: input[span.clone()].into(),
+                        span,
+                    });
+                    unrecognized.sort_by_key(|u| u.span.start);
+                }
+
+                Some(*first)
+            }
+        };
+
+        Ok(Self {
+            recur,
+            time,
+            unrecognized,
+        })
+    }
+
+    /// Build a [`RRule`] starting at the given `date`, with the time of the phrase and timezone `tz`
+    ///
+    /// UNTIL is converted to the value type required by the resulting DTSTART.
+    pub fn to_rrule(
+        &self,
+        date: NaiveDate,
+        tz: Option<DefaultZone>,
+    ) -> Result<RRule, RRuleVerifyError> {
+        let dt = match self.time {
+            Some(time) => Dt::DateTimeLocal(date.and_time(time)),
+            None => Dt::Date(date),
+        };
+
+        let mut recur = self.recur.clone();
+
+        recur.until = recur.until.map(|until| match (until, dt) {
+            (Dt::Date(until), Dt::DateTimeLocal(_)) => {
+                let end_of_day = until.and_hms_opt(23, 59, 59).expect("valid time");
+
+                match tz {
+                    Some(tz) => {
+                        Dt::DateTimeUtc(local_datetime_with_tz(end_of_day, tz).with_timezone(&Utc))
+                    }
+                    None => Dt::DateTimeLocal(end_of_day),
+                }
+            }
+            (until, _) => until,
+        });
+
+        let rrule = RRule::new(DtStart(DtProperty { dt, tz }), recur);
+        rrule.verify(false)?;
+
+        Ok(rrule)
+    }
+}
+
+struct Token {
+    text: String,
+    span: Range<usize>,
+}
+
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut start = None;
+
+    let is_separator = |c: char| c.is_whitespace() || matches!(c, ',' | ';' | '(' | ')');
+
+    for (i, c) in input.char_indices().chain(Some((input.len(), ' '))) {
+        match (start, is_separator(c)) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                let text = input[s..i].trim_end_matches('.');
+
+                tokens.push(Token {
+                    text: text.to_lowercase(),
+                    span: s..s + text.len(),
+                });
+
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+struct Parser<'t> {
+    tokens: &'t [Token],
+    pos: usize,
+    today: NaiveDate,
+
+    freq: Option<Frequency>,
+    interval: Option<u32>,
+    count: Option<u32>,
+    until: Option<NaiveDate>,
+    by_day: Vec<ByDay>,
+    by_month_day: Vec<i32>,
+    by_year_day: Vec<i32>,
+    by_month: Vec<u32>,
+    by_set_pos: Vec<i32>,
+    // ordinal weekdays were scoped to the year, e.g. "20th monday of the year"
+    yearly_scope: bool,
+    times: Vec<NaiveTime>,
+    times_span: Option<Range<usize>>,
+
+    unrecognized: Vec<Range<usize>>,
+}
+
+impl Parser<'_> {
+    fn run(&mut self) {
+        while self.pos < self.tokens.len() {
+            let matched = self.every()
+                || self.frequency_word()
+                || self.nth_weekday()
+                || self.month_days()
+                || self.weekdays()
+                || self.months()
+                || self.times()
+                || self.until()
+                || self.count()
+                || self.filler();
+
+            if !matched {
+                self.unrecognized.push(self.tokens[self.pos].span.clone());
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<&str> {
+        self.tokens.get(self.pos + offset).map(|t| t.text.as_str())
+    }
+
+    fn eat(&mut self, words: &[&str]) -> bool {
+        if self.peek(0).is_some_and(|t| words.contains(&t)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Run `f`, resetting the position if it fails
+    fn attempt<T>(&mut self, f: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        let pos = self.pos;
+        let result = f(self);
+
+        if result.is_none() {
+            self.pos = pos;
+        }
+
+        result
+    }
+
+    fn token<T>(&mut self, f: impl Fn(&str) -> Option<T>) -> Option<T> {
+        let value = f(self.peek(0)?)?;
+        self.pos += 1;
+        Some(value)
+    }
+
+    /// "monday, wednesday and friday"
+    fn weekday_list(&mut self) -> Option<Vec<Weekday>> {
+        let mut weekdays = vec![self.token(parse_weekday)?];
+
+        loop {
+            let next = self.attempt(|this| {
+                this.eat(&["and", "&", "or"]);
+                this.token(parse_weekday)
+            });
+
+            match next {
+                Some(weekday) => weekdays.push(weekday),
+                None => return Some(weekdays),
+            }
+        }
+    }
+
+    /// "first and third", "last"
+    fn ordinal_list(&mut self) -> Option<Vec<i32>> {
+        let mut ordinals = vec![self.ordinal()?];
+
+        loop {
+            let next = self.attempt(|this| {
+                this.eat(&["and", "&", "or"]);
+                this.ordinal()
+            });
+
+            match next {
+                Some(ordinal) => ordinals.push(ordinal),
+                None => return Some(ordinals),
+            }
+        }
+    }
+
+    /// "first", "2nd", "last", "second to last"
+    fn ordinal(&mut self) -> Option<i32> {
+        self.attempt(|this| {
+            if this.eat(&["last"]) {
+                return Some(-1);
+            }
+
+            let n = this.token(parse_ordinal)?;
+
+            let from_end = this.attempt(|this| {
+                this.eat(&["to"]);
+                this.eat(&["last"]).then_some(())
+            });
+
+            Some(if from_end.is_some() { -n } else { n })
+        })
+    }
+
+    /// "of the month", "of each year"
+    fn scope(&mut self) -> Option<Frequency> {
+        self.attempt(|this| {
+            this.eat(&["of", "in"]).then_some(())?;
+            this.eat(&["the", "each", "every"]);
+            this.token(|t| match t {
+                "month" => Some(Frequency::Monthly),
+                "year" => Some(Frequency::Yearly),
+                _ => None,
+            })
+        })
+    }
+
+    /// "every other friday", "every 2 weeks", "every weekday", "each month"
+    fn every(&mut self) -> bool {
+        self.attempt(|this| {
+            this.eat(&["every", "each"]).then_some(())?;
+
+            let interval = if this.eat(&["other"]) {
+                Some(2)
+            } else {
+                this.attempt(|this| {
+                    let n = this.token(|t| parse_number(t).or_else(|| parse_ordinal(t)))?;
+
+                    // "every second" is a unit, "every second week" an interval
+                    let next = this.peek(0)?;
+                    (parse_unit(next).is_some()
+                        || parse_weekday(next).is_some()
+                        || matches!(next, "weekday" | "weekdays" | "weekend" | "weekends"))
+                    .then_some(n as u32)
+                })
+            };
+
+            if let Some(freq) = this.token(parse_unit) {
+                this.freq = Some(freq);
+            } else if this.eat(&["weekday", "weekdays"]) {
+                this.freq.get_or_insert(Frequency::Weekly);
+                this.by_day.extend(WEEKDAYS.iter().copied().map(ByDay::All));
+            } else if this.eat(&["weekend", "weekends"]) {
+                this.freq.get_or_insert(Frequency::Weekly);
+                this.by_day.extend(WEEKEND.iter().copied().map(ByDay::All));
+            } else if let Some(weekdays) = this.weekday_list() {
+                this.freq.get_or_insert(Frequency::Weekly);
+                this.by_day.extend(weekdays.into_iter().map(ByDay::All));
+            } else if let Some(month) = this.token(parse_month) {
+                this.freq.get_or_insert(Frequency::Yearly);
+                this.by_month.push(month);
+            } else {
+                return None;
+            }
+
+            if interval.is_some() {
+                this.interval = interval;
+            }
+
+            Some(())
+        })
+        .is_some()
+    }
+
+    /// "daily", "weekly", "fortnightly"
+    fn frequency_word(&mut self) -> bool {
+        let (freq, interval) = match self.peek(0) {
+            Some("secondly") => (Frequency::Secondly, None),
+            Some("minutely") => (Frequency::Minutely, None),
+            Some("hourly") => (Frequency::Hourly, None),
+            Some("daily") => (Frequency::Daily, None),
+            Some("weekly") => (Frequency::Weekly, None),
+            Some("fortnightly" | "biweekly") => (Frequency::Weekly, Some(2)),
+            Some("monthly") => (Frequency::Monthly, None),
+            Some("yearly" | "annually") => (Frequency::Yearly, None),
+            _ => return false,
+        };
+
+        self.pos += 1;
+        self.freq = Some(freq);
+
+        if interval.is_some() {
+            self.interval = interval;
+        }
+
+        true
+    }
+
+    /// "first monday of each month", "last weekday of the month", "last day of the month"
+    fn nth_weekday(&mut self) -> bool {
+        self.attempt(|this| {
+            this.eat(&["on"]);
+            this.eat(&["the"]);
+
+            let ordinals = this.ordinal_list()?;
+
+            if this.eat(&["weekday"]) {
+                this.by_day.extend(WEEKDAYS.iter().copied().map(ByDay::All));
+                this.by_set_pos.extend(&ordinals);
+            } else if this.eat(&["day"]) {
+                // "first day of the month" needs a scope, "first day" alone is ambiguous
+                let scope = this.scope()?;
+                this.scoped_days(scope, &ordinals)?;
+
+                return Some(());
+            } else {
+                let weekdays = this.weekday_list()?;
+
+                for nth in &ordinals {
+                    for &weekday in &weekdays {
+                        this.by_day.push(ByDay::Nth(weekday, *nth));
+                    }
+                }
+            }
+
+            if let Some(scope) = this.scope() {
+                this.yearly_scope |= scope == Frequency::Yearly;
+            }
+
+            Some(())
+        })
+        .is_some()
+    }
+
+    /// "on the 1st and 15th", "the 15th day of each month"
+    fn month_days(&mut self) -> bool {
+        self.attempt(|this| {
+            this.eat(&["on"]);
+            this.eat(&["the"]);
+
+            let days = this.ordinal_list()?;
+
+            this.eat(&["day", "days"]);
+
+            let scope = this.scope().unwrap_or(Frequency::Monthly);
+            this.scoped_days(scope, &days)
+        })
+        .is_some()
+    }
+
+    /// Days of the month or, scoped to the year, days of the year
+    fn scoped_days(&mut self, scope: Frequency, days: &[i32]) -> Option<()> {
+        if scope == Frequency::Yearly {
+            if days.iter().any(|day| !matches!(day, 1..=366 | -366..=-1)) {
+                return None;
+            }
+
+            self.yearly_scope = true;
+            self.by_year_day.extend(days);
+        } else {
+            if days.iter().any(|day| !matches!(day, 1..=31 | -31..=-1)) {
+                return None;
+            }
+
+            self.by_month_day.extend(days);
+        }
+
+        Some(())
+    }
+
+    /// "on mondays and fridays"
+    fn weekdays(&mut self) -> bool {
+        self.attempt(|this| {
+            this.eat(&["on"]);
+
+            let by_day: Vec<ByDay> = if this.eat(&["weekdays"]) {
+                WEEKDAYS.iter().copied().map(ByDay::All).collect()
+            } else if this.eat(&["weekends"]) {
+                WEEKEND.iter().copied().map(ByDay::All).collect()
+            } else {
+                this.weekday_list()?.into_iter().map(ByDay::All).collect()
+            };
+
+            this.by_day.extend(by_day);
+
+            Some(())
+        })
+        .is_some()
+    }
+
+    /// "in january and march", "on june 15"
+    fn months(&mut self) -> bool {
+        self.attempt(|this| {
+            this.eat(&["in", "on"]);
+
+            let mut months = vec![this.token(parse_month)?];
+
+            loop {
+                let day = this.token(parse_day);
+
+                if let Some(day) = day {
+                    this.by_month_day.push(day as i32);
+                }
+
+                let next = this.attempt(|this| {
+                    this.eat(&["and", "&", "or"]);
+                    this.token(parse_month)
+                });
+
+                match next {
+                    Some(month) => months.push(month),
+                    None => break,
+                }
+            }
+
+            this.by_month.extend(months);
+
+            Some(())
+        })
+        .is_some()
+    }
+
+    /// "at 9am and 5pm", "at noon"
+    fn times(&mut self) -> bool {
+        let start = self.pos;
+
+        let result = self.attempt(|this| {
+            this.eat(&["at", "@"]);
+
+            let mut times = vec![this.time()?];
+
+            while let Some(time) = this.attempt(|this| {
+                this.eat(&["and", "&"]);
+                this.time()
+            }) {
+                times.push(time);
+            }
+
+            this.times.extend(times);
+
+            Some(())
+        });
+
+        if result.is_some() {
+            let span = self.tokens[start].span.start..self.tokens[self.pos - 1].span.end;
+
+            self.times_span = Some(match self.times_span.take() {
+                Some(prev) => prev.start.min(span.start)..prev.end.max(span.end),
+                None => span,
+            });
+        }
+
+        result.is_some()
+    }
+
+    /// "9am", "9:30 pm", "17:00", "noon"
+    fn time(&mut self) -> Option<NaiveTime> {
+        self.attempt(|this| {
+            match this.peek(0)? {
+                "noon" | "midday" => {
+                    this.pos += 1;
+                    return NaiveTime::from_hms_opt(12, 0, 0);
+                }
+                "midnight" => {
+                    this.pos += 1;
+                    return NaiveTime::from_hms_opt(0, 0, 0);
+                }
+                _ => {}
+            }
+
+            let token = this.peek(0)?.to_owned();
+
+            let (clock, suffix) = match token.find(|c: char| c.is_ascii_alphabetic()) {
+                Some(idx) => token.split_at(idx),
+                None => (token.as_str(), ""),
+            };
+
+            let (hour, minute) = match clock.split_once(':') {
+                Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+                None => (clock.parse::<u32>().ok()?, 0),
+            };
+
+            let has_colon = clock.contains(':');
+            this.pos += 1;
+
+            let suffix = if suffix.is_empty() {
+                this.token(|t| {
+                    matches!(t, "am" | "pm" | "a.m" | "p.m" | "o'clock").then(|| t.to_owned())
+                })
+                .unwrap_or_default()
+            } else {
+                suffix.to_owned()
+            };
+
+            let hour = match suffix.as_str() {
+                "am" | "a.m" if hour == 12 => 0,
+                "am" | "a.m" if hour < 12 => hour,
+                "pm" | "p.m" if hour == 12 => 12,
+                "pm" | "p.m" if hour < 12 => hour + 12,
+                "o'clock" if hour <= 12 => hour,
+                // bare numbers are only times with a colon
+                "" if has_colon => hour,
+                _ => return None,
+            };
+
+            NaiveTime::from_hms_opt(hour, minute, 0)
+        })
+    }
+
+    /// "until june", "until dec 31, 2025", "until 2025-06-30"
+    fn until(&mut self) -> bool {
+        self.attempt(|this| {
+            this.eat(&["until", "till", "til", "through", "thru"])
+                .then_some(())?;
+
+            let date = this.date()?;
+            this.until = Some(date);
+
+            Some(())
+        })
+        .is_some()
+    }
+
+    fn date(&mut self) -> Option<NaiveDate> {
+        if let Some(date) = self.token(|t| NaiveDate::parse_from_str(t, "%Y-%m-%d").ok()) {
+            return Some(date);
+        }
+
+        self.attempt(|this| {
+            let (month, day) = if let Some(month) = this.token(parse_month) {
+                (month, this.token(parse_day))
+            } else {
+                let day = this.token(parse_day)?;
+                this.eat(&["of"]);
+                (this.token(parse_month)?, Some(day))
+            };
+
+            let year = this.token(|t| t.parse::<i32>().ok().filter(|&y| y > 999));
+
+            let date = match (day, year) {
+                (Some(day), Some(year)) => NaiveDate::from_ymd_opt(year, month, day)?,
+                (Some(day), None) => {
+                    let date = NaiveDate::from_ymd_opt(this.today.year(), month, day)?;
+
+                    if date < this.today {
+                        NaiveDate::from_ymd_opt(this.today.year() + 1, month, day)?
+                    } else {
+                        date
+                    }
+                }
+                // "until june" ends right before june starts
+                (None, year) => {
+                    let year = year.unwrap_or_else(|| {
+                        if month > this.today.month() {
+                            this.today.year()
+                        } else {
+                            this.today.year() + 1
+                        }
+                    });
+
+                    NaiveDate::from_ymd_opt(year, month, 1)? - Duration::days(1)
+                }
+            };
+
+            Some(date)
+        })
+    }
+
+    /// "10 times", "for 5 occurrences", "once"
+    fn count(&mut self) -> bool {
+        self.attempt(|this| {
+            this.eat(&["for"]);
+
+            let count = if this.eat(&["once"]) {
+                1
+            } else {
+                let n = this.token(parse_number)?;
+                this.eat(&["times", "occurrences", "time"]).then_some(())?;
+                n as u32
+            };
+
+            this.count = Some(count);
+
+            Some(())
+        })
+        .is_some()
+    }
+
+    fn filler(&mut self) -> bool {
+        self.eat(&["and", "on", "the", "of", "at", "in", "&", "starting"])
+    }
+}
+
+fn parse_weekday(t: &str) -> Option<Weekday> {
+    let t = t.strip_suffix('s').filter(|t| t.len() > 3).unwrap_or(t);
+
+    Some(match t {
+        "monday" | "mon" => Weekday::Monday,
+        "tuesday" | "tue" | "tues" => Weekday::Tuesday,
+        "wednesday" | "wed" => Weekday::Wednesday,
+        "thursday" | "thu" | "thur" | "thurs" => Weekday::Thursday,
+        "friday" | "fri" => Weekday::Friday,
+        "saturday" | "sat" => Weekday::Saturday,
+        "sunday" | "sun" => Weekday::Sunday,
+        _ => return None,
+    })
+}
+
+fn parse_month(t: &str) -> Option<u32> {
+    Some(match t {
+        "january" | "jan" => 1,
+        "february" | "feb" => 2,
+        "march" | "mar" => 3,
+        "april" | "apr" => 4,
+        "may" => 5,
+        "june" | "jun" => 6,
+        "july" | "jul" => 7,
+        "august" | "aug" => 8,
+        "september" | "sep" | "sept" => 9,
+        "october" | "oct" => 10,
+        "november" | "nov" => 11,
+        "december" | "dec" => 12,
+        _ => return None,
+    })
+}
+
+fn parse_unit(t: &str) -> Option<Frequency> {
+    let t = t.strip_suffix('s').unwrap_or(t);
+
+    Some(match t {
+        "second" | "sec" => Frequency::Secondly,
+        "minute" | "min" => Frequency::Minutely,
+        "hour" | "hr" => Frequency::Hourly,
+        "day" => Frequency::Daily,
+        "week" => Frequency::Weekly,
+        "month" => Frequency::Monthly,
+        "year" => Frequency::Yearly,
+        _ => return None,
+    })
+}
+
+fn parse_number(t: &str) -> Option<i32> {
+    const WORDS: [&str; 12] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven",
+        "twelve",
+    ];
+
+    if let Some(idx) = WORDS.iter().position(|&w| w == t) {
+        return Some(idx as i32 + 1);
+    }
+
+    t.parse().ok().filter(|&n| n > 0)
+}
+
+fn parse_ordinal(t: &str) -> Option<i32> {
+    const WORDS: [&str; 12] = [
+        "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth",
+        "tenth", "eleventh", "twelfth",
+    ];
+
+    if let Some(idx) = WORDS.iter().position(|&w| w == t) {
+        return Some(idx as i32 + 1);
+    }
+
+    let digits = t
+        .strip_suffix("st")
+        .or_else(|| t.strip_suffix("nd"))
+        .or_else(|| t.strip_suffix("rd"))
+        .or_else(|| t.strip_suffix("th"))?;
+
+    digits.parse().ok().filter(|&n| n > 0)
+}
+
+fn parse_day(t: &str) -> Option<u32> {
+    let day = parse_ordinal(t).or_else(|| t.parse().ok())?;
+
+    (1..=31).contains(&day).then_some(day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn parse(input: &str) -> Phrase {
+        Phrase::parse(input, NaiveDate::from_ymd_opt(2022, 3, 10).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn phrases() {
+        let phrase = parse("every weekday at 9am");
+        assert_eq!(phrase.recur.to_string(), "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR");
+        assert_eq!(phrase.time, NaiveTime::from_hms_opt(9, 0, 0));
+        assert!(phrase.unrecognized.is_empty());
+
+        let phrase = parse("first Monday of each month");
+        assert_eq!(phrase.recur.to_string(), "FREQ=MONTHLY;BYDAY=1MO");
+        assert_eq!(phrase.time, None);
+
+        let phrase = parse("every other Friday until June");
+        assert_eq!(
+            phrase.recur.to_string(),
+            "FREQ=WEEKLY;UNTIL=20220531;INTERVAL=2;BYDAY=FR"
+        );
+
+        let phrase = parse("last weekday of the month at 17:30, 12 times");
+        assert_eq!(
+            phrase.recur.to_string(),
+            "FREQ=MONTHLY;COUNT=12;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1"
+        );
+        assert_eq!(phrase.time, NaiveTime::from_hms_opt(17, 30, 0));
+
+        let phrase = parse("monthly on the 1st and 15th at 9am and 5pm");
+        assert_eq!(
+            phrase.recur.to_string(),
+            "FREQ=MONTHLY;BYMINUTE=0;BYHOUR=9,17;BYMONTHDAY=1,15"
+        );
+
+        let phrase = parse("first day of the year");
+        assert_eq!(phrase.recur.to_string(), "FREQ=YEARLY;BYYEARDAY=1");
+
+        let phrase = parse("the last day of each year");
+        assert_eq!(phrase.recur.to_string(), "FREQ=YEARLY;BYYEARDAY=-1");
+
+        let phrase = parse("100th day of the year");
+        assert_eq!(phrase.recur.to_string(), "FREQ=YEARLY;BYYEARDAY=100");
+    }
+
+    #[test]
+    fn unrecognized() {
+        let phrase = parse("every tuesday with cake at noon");
+        assert_eq!(phrase.recur.to_string(), "FREQ=WEEKLY;BYDAY=TU");
+        assert_eq!(
+            phrase.unrecognized,
+            vec![Unrecognized {
+                span: 14..23,
+                text: "with cake".into()
+            }]
+        );
+
+        assert!(matches!(
+            Phrase::parse("whenever", NaiveDate::from_ymd_opt(2022, 1, 1).unwrap()),
+            Err(PhraseError::NoFrequency)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn to_rrule() {
+        let phrase = parse("every other Friday at 10:00 until June");
+        let rrule = phrase
+            .to_rrule(
+                NaiveDate::from_ymd_opt(2022, 3, 11).unwrap(),
+                Some(chrono_tz::Europe::Berlin),
+            )
+            .unwrap();
+
+        assert_eq!(rrule.recur().until.unwrap().to_string(), "20220531T215959Z");
+    }
+}
@@ -1,16 +1,22 @@
 use crate::byday::ByDay;
 use crate::dt::Dt;
-use crate::error::IResult;
+use crate::error::{expected, rule_part, IResult, ParseError};
 use crate::freq::Frequency;
 use crate::util::{display_list, parse_i32, parse_list, parse_u32};
 use crate::weekday::Weekday;
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use core::hash::{Hash, Hasher};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::combinator::{cut, map, map_res};
+use nom::combinator::{cut, map};
 use nom::error::context;
 use nom::sequence::preceded;
-use std::fmt;
 
+/// A recurrence rule
+///
+/// Equality and hashing compare the [normalized](Recur::normalize) rules, so `BYDAY=MO,TU` equals
+/// `BYDAY=TU,MO;INTERVAL=1`.
 #[derive(Debug, Clone)]
 pub struct Recur {
     pub freq: Frequency,
@@ -46,53 +52,115 @@ pub enum RecurRulePart {
     WeekStart(Weekday),
 }
 
+const RULE_PARTS: &[&str] = &[
+    "FREQ",
+    "UNTIL",
+    "COUNT",
+    "INTERVAL",
+    "BYSECOND",
+    "BYMINUTE",
+    "BYHOUR",
+    "BYDAY",
+    "BYMONTHDAY",
+    "BYYEARDAY",
+    "BYWEEKNO",
+    "BYMONTH",
+    "BYSETPOS",
+    "WKST",
+];
+
 impl RecurRulePart {
     pub(crate) fn parse(i: &str) -> IResult<&str, Self> {
-        context(
-            "invalid rule part",
-            alt((
-                map(preceded(tag("FREQ="), cut(Frequency::parse)), Self::Freq),
-                map(preceded(tag("UNTIL="), cut(Dt::parse)), Self::Until),
-                map(preceded(tag("COUNT="), cut(parse_u32)), Self::Count),
-                map(preceded(tag("INTERVAL="), cut(parse_u32)), Self::Interval),
-                map(
-                    preceded(tag("BYSECOND="), cut(parse_list(parse_u32, ','))),
-                    Self::BySecond,
-                ),
-                map(
-                    preceded(tag("BYMINUTE="), cut(parse_list(parse_u32, ','))),
-                    Self::ByMinute,
-                ),
-                map(
-                    preceded(tag("BYHOUR="), cut(parse_list(parse_u32, ','))),
-                    Self::ByHour,
-                ),
-                map(
-                    preceded(tag("BYDAY="), cut(parse_list(ByDay::parse, ','))),
-                    Self::ByDay,
-                ),
-                map(
-                    preceded(tag("BYMONTHDAY="), cut(parse_list(parse_i32, ','))),
-                    Self::ByMonthDay,
-                ),
-                map(
-                    preceded(tag("BYYEARDAY="), cut(parse_list(parse_i32, ','))),
-                    Self::ByYearDay,
-                ),
-                map(
-                    preceded(tag("BYWEEKNO="), cut(parse_list(parse_i32, ','))),
-                    Self::ByWeekNo,
-                ),
-                map(
-                    preceded(tag("BYMONTH="), cut(parse_list(parse_u32, ','))),
-                    Self::ByMonth,
-                ),
-                map(
-                    preceded(tag("BYSETPOS="), cut(parse_list(parse_i32, ','))),
-                    Self::BySetPos,
-                ),
-                map(preceded(tag("WKST="), cut(Weekday::parse)), Self::WeekStart),
-            )),
+        expected(
+            RULE_PARTS,
+            context(
+                "invalid rule part",
+                alt((
+                    map(
+                        preceded(tag("FREQ="), cut(rule_part("FREQ", Frequency::parse))),
+                        Self::Freq,
+                    ),
+                    map(
+                        preceded(tag("UNTIL="), cut(rule_part("UNTIL", Dt::parse))),
+                        Self::Until,
+                    ),
+                    map(
+                        preceded(tag("COUNT="), cut(rule_part("COUNT", parse_u32))),
+                        Self::Count,
+                    ),
+                    map(
+                        preceded(tag("INTERVAL="), cut(rule_part("INTERVAL", parse_u32))),
+                        Self::Interval,
+                    ),
+                    map(
+                        preceded(
+                            tag("BYSECOND="),
+                            cut(rule_part("BYSECOND", parse_list(parse_u32, ','))),
+                        ),
+                        Self::BySecond,
+                    ),
+                    map(
+                        preceded(
+                            tag("BYMINUTE="),
+                            cut(rule_part("BYMINUTE", parse_list(parse_u32, ','))),
+                        ),
+                        Self::ByMinute,
+                    ),
+                    map(
+                        preceded(
+                            tag("BYHOUR="),
+                            cut(rule_part("BYHOUR", parse_list(parse_u32, ','))),
+                        ),
+                        Self::ByHour,
+                    ),
+                    map(
+                        preceded(
+                            tag("BYDAY="),
+                            cut(rule_part("BYDAY", parse_list(ByDay::parse, ','))),
+                        ),
+                        Self::ByDay,
+                    ),
+                    map(
+                        preceded(
+                            tag("BYMONTHDAY="),
+                            cut(rule_part("BYMONTHDAY", parse_list(parse_i32, ','))),
+                        ),
+                        Self::ByMonthDay,
+                    ),
+                    map(
+                        preceded(
+                            tag("BYYEARDAY="),
+                            cut(rule_part("BYYEARDAY", parse_list(parse_i32, ','))),
+                        ),
+                        Self::ByYearDay,
+                    ),
+                    map(
+                        preceded(
+                            tag("BYWEEKNO="),
+                            cut(rule_part("BYWEEKNO", parse_list(parse_i32, ','))),
+                        ),
+                        Self::ByWeekNo,
+                    ),
+                    map(
+                        preceded(
+                            tag("BYMONTH="),
+                            cut(rule_part("BYMONTH", parse_list(parse_u32, ','))),
+                        ),
+                        Self::ByMonth,
+                    ),
+                    map(
+                        preceded(
+                            tag("BYSETPOS="),
+                            cut(rule_part("BYSETPOS", parse_list(parse_i32, ','))),
+                        ),
+                        Self::BySetPos,
+                    ),
+                    map(
+                        preceded(tag("WKST="), cut(rule_part("WKST", Weekday::parse))),
+                        Self::WeekStart,
+                    ),
+                )),
+            ),
         )(i)
     }
 }
@@ -109,110 +177,164 @@ macro_rules! ensure {
 pub enum RecurParseError {
     #[error("duplicate property {0}")]
     DuplicateProperty(&'static str),
+    #[error("missing FREQ")]
+    MissingFreq,
+}
+
+impl RecurParseError {
+    /// Rule part the error is about
+    fn rule_part(&self) -> &'static str {
+        match self {
+            Self::DuplicateProperty(part) => part,
+            Self::MissingFreq => "FREQ",
+        }
+    }
 }
 
 impl Recur {
+    pub fn new(freq: Frequency) -> Self {
+        Self {
+            freq,
+            until: None,
+            count: None,
+            interval: None,
+            by_second: vec![],
+            by_minute: vec![],
+            by_hour: vec![],
+            by_day: vec![],
+            by_month_day: vec![],
+            by_year_day: vec![],
+            by_week_no: vec![],
+            by_month: vec![],
+            by_set_pos: vec![],
+            week_start: None,
+        }
+    }
+
     pub fn parse(i: &str) -> IResult<&str, Self> {
-        map_res(
-            parse_list(RecurRulePart::parse, ';'),
-            |items| -> Result<Self, RecurParseError> {
-                let mut freq = None;
-                let mut until = None;
-                let mut count = None;
-                let mut interval = None;
-                let mut by_second = vec![];
-                let mut by_minute = vec![];
-                let mut by_hour = vec![];
-                let mut by_day = vec![];
-                let mut by_month_day = vec![];
-                let mut by_year_day = vec![];
-                let mut by_week_no = vec![];
-                let mut by_month = vec![];
-                let mut by_set_pos = vec![];
-                let mut week_start = None;
-
-                for item in items {
-                    match item {
-                        RecurRulePart::Freq(f) => {
-                            ensure!(freq.is_none(), "FREQ");
-                            freq = Some(f);
-                        }
-                        RecurRulePart::Until(u) => {
-                            ensure!(until.is_none(), "UNTIL");
-                            until = Some(u)
-                        }
-                        RecurRulePart::Count(c) => {
-                            ensure!(count.is_none(), "COUNT");
-                            count = Some(c);
-                        }
-                        RecurRulePart::Interval(i) => {
-                            ensure!(interval.is_none(), "INTERVAL");
-                            interval = Some(i);
-                        }
-                        RecurRulePart::BySecond(b) => {
-                            ensure!(by_second.is_empty(), "BYSECOND");
-                            by_second = b;
-                        }
-                        RecurRulePart::ByMinute(b) => {
-                            ensure!(by_minute.is_empty(), "BYMINUTE");
-                            by_minute = b;
-                        }
-                        RecurRulePart::ByHour(b) => {
-                            ensure!(by_hour.is_empty(), "BYHOUR");
-                            by_hour = b;
-                        }
-                        RecurRulePart::ByDay(b) => {
-                            ensure!(by_day.is_empty(), "BYDAY");
-                            by_day = b;
-                        }
-                        RecurRulePart::ByMonthDay(b) => {
-                            ensure!(by_month_day.is_empty(), "BYMONTHDAY");
-                            by_month_day = b;
-                        }
-                        RecurRulePart::ByYearDay(b) => {
-                            ensure!(by_year_day.is_empty(), "BYYEARDAY");
-                            by_year_day = b;
-                        }
-                        RecurRulePart::ByWeekNo(b) => {
-                            ensure!(by_week_no.is_empty(), "BYWEEKNO");
-                            by_week_no = b;
-                        }
-                        RecurRulePart::ByMonth(b) => {
-                            ensure!(by_month.is_empty(), "BYMONTH");
-                            by_month = b;
-                        }
-                        RecurRulePart::BySetPos(b) => {
-                            ensure!(by_set_pos.is_empty(), "BYSETPOS");
-                            by_set_pos = b;
-                        }
-                        RecurRulePart::WeekStart(w) => {
-                            ensure!(week_start.is_none(), "WKST");
-                            week_start = Some(w);
-                        }
-                    }
+        let (rem, items) = parse_list(RecurRulePart::parse, ';')(i)?;
+
+        match Self::from_parts(items) {
+            Ok(this) => Ok((rem, this)),
+            Err(e) => Err(nom::Err::Failure(ParseError::rule_part_error(
+                i,
+                e.rule_part(),
+                e,
+            ))),
+        }
+    }
+
+    fn from_parts(items: Vec<RecurRulePart>) -> Result<Self, RecurParseError> {
+        let mut freq = None;
+        let mut until = None;
+        let mut count = None;
+        let mut interval = None;
+        let mut by_second = vec![];
+        let mut by_minute = vec![];
+        let mut by_hour = vec![];
+        let mut by_day = vec![];
+        let mut by_month_day = vec![];
+        let mut by_year_day = vec![];
+        let mut by_week_no = vec![];
+        let mut by_month = vec![];
+        let mut by_set_pos = vec![];
+        let mut week_start = None;
+
+        for item in items {
+            match item {
+                RecurRulePart::Freq(f) => {
+                    ensure!(freq.is_none(), "FREQ");
+                    freq = Some(f);
+                }
+                RecurRulePart::Until(u) => {
+                    ensure!(until.is_none(), "UNTIL");
+                    until = Some(u)
                 }
+                RecurRulePart::Count(c) => {
+                    ensure!(count.is_none(), "COUNT");
+                    count = Some(c);
+                }
+                RecurRulePart::Interval(i) => {
+                    ensure!(interval.is_none(), "INTERVAL");
+                    interval = Some(i);
+                }
+                RecurRulePart::BySecond(b) => {
+                    ensure!(by_second.is_empty(), "BYSECOND");
+                    by_second = b;
+                }
+                RecurRulePart::ByMinute(b) => {
+                    ensure!(by_minute.is_empty(), "BYMINUTE");
+                    by_minute = b;
+                }
+                RecurRulePart::ByHour(b) => {
+                    ensure!(by_hour.is_empty(), "BYHOUR");
+                    by_hour = b;
+                }
+                RecurRulePart::ByDay(b) => {
+                    ensure!(by_day.is_empty(), "BYDAY");
+                    by_day = b;
+                }
+                RecurRulePart::ByMonthDay(b) => {
+                    ensure!(by_month_day.is_empty(), "BYMONTHDAY");
+                    by_month_day = b;
+                }
+                RecurRulePart::ByYearDay(b) => {
+                    ensure!(by_year_day.is_empty(), "BYYEARDAY");
+                    by_year_day = b;
+                }
+                RecurRulePart::ByWeekNo(b) => {
+                    ensure!(by_week_no.is_empty(), "BYWEEKNO");
+                    by_week_no = b;
+                }
+                RecurRulePart::ByMonth(b) => {
+                    ensure!(by_month.is_empty(), "BYMONTH");
+                    by_month = b;
+                }
+                RecurRulePart::BySetPos(b) => {
+                    ensure!(by_set_pos.is_empty(), "BYSETPOS");
+                    by_set_pos = b;
+                }
+                RecurRulePart::WeekStart(w) => {
+                    ensure!(week_start.is_none(), "WKST");
+                    week_start = Some(w);
+                }
+            }
+        }
 
-                let this = Self {
-                    // TODO error handling :)
-                    freq: freq.unwrap(),
-                    until,
-                    count,
-                    interval,
-                    by_second,
-                    by_minute,
-                    by_hour,
-                    by_day,
-                    by_month_day,
-                    by_year_day,
-                    by_week_no,
-                    by_month,
-                    by_set_pos,
-                    week_start,
-                };
-
-                Ok(this)
-            },
-        )(i)
+        let this = Self {
+            freq: freq.ok_or(RecurParseError::MissingFreq)?,
+            until,
+            count,
+            interval,
+            by_second,
+            by_minute,
+            by_hour,
+            by_day,
+            by_month_day,
+            by_year_day,
+            by_week_no,
+            by_month,
+            by_set_pos,
+            week_start,
+        };
+
+        Ok(this)
+    }
+
+    /// Bring the rule into its canonical form
+    ///
+    /// Lists are sorted and de-duplicated and parts set to their default, `INTERVAL=1` and
+    /// `WKST=MO`, are removed. Displaying a normalized rule writes its parts in a fixed order.
+    pub fn normalize(&mut self) {
+        self.sort_and_dedup();
+
+        if self.interval == Some(1) {
+            self.interval = None;
+        }
+
+        if self.week_start == Some(Weekday::Monday) {
+            self.week_start = None;
+        }
     }
 
     pub(crate) fn sort_and_dedup(&mut self) {
@@ -238,6 +360,55 @@ impl Recur {
     }
 }
 
+impl PartialEq for Recur {
+    fn eq(&self, other: &Self) -> bool {
+        let mut this = self.clone();
+        let mut other = other.clone();
+
+        this.normalize();
+        other.normalize();
+
+        this.freq == other.freq
+            && this.until == other.until
+            && this.count == other.count
+            && this.interval == other.interval
+            && this.by_second == other.by_second
+            && this.by_minute == other.by_minute
+            && this.by_hour == other.by_hour
+            && this.by_day == other.by_day
+            && this.by_month_day == other.by_month_day
+            && this.by_year_day == other.by_year_day
+            && this.by_week_no == other.by_week_no
+            && this.by_month == other.by_month
+            && this.by_set_pos == other.by_set_pos
+            && this.week_start == other.week_start
+    }
+}
+
+impl Eq for Recur {}
+
+impl Hash for Recur {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut this = self.clone();
+        this.normalize();
+
+        this.freq.hash(state);
+        this.until.hash(state);
+        this.count.hash(state);
+        this.interval.hash(state);
+        this.by_second.hash(state);
+        this.by_minute.hash(state);
+        this.by_hour.hash(state);
+        this.by_day.hash(state);
+        this.by_month_day.hash(state);
+        this.by_year_day.hash(state);
+        this.by_week_no.hash(state);
+        this.by_month.hash(state);
+        this.by_set_pos.hash(state);
+        this.week_start.hash(state);
+    }
+}
+
 impl fmt::Display for Recur {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "FREQ={}", self.freq)?;
@@ -271,3 +442,34 @@ impl fmt::Display for Recur {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn recur(s: &str) -> Recur {
+        Recur::parse(s).unwrap().1
+    }
+
+    #[test]
+    fn normalize() {
+        let mut r = recur("FREQ=WEEKLY;WKST=MO;BYDAY=TU,MO,TU;INTERVAL=1;COUNT=3");
+        r.normalize();
+
+        assert_eq!(r.to_string(), "FREQ=WEEKLY;COUNT=3;BYDAY=MO,TU");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn eq_and_hash() {
+        use std::collections::HashSet;
+
+        let a = recur("FREQ=WEEKLY;BYDAY=MO,TU");
+        let b = recur("FREQ=WEEKLY;INTERVAL=1;BYDAY=TU,MO");
+
+        assert_eq!(a, b);
+        assert_ne!(a, recur("FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,MO"));
+        assert_eq!(HashSet::from([a, b]).len(), 1);
+    }
+}
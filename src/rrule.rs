@@ -4,15 +4,18 @@ use crate::dt_prop::DtStart;
 use crate::error::{IResult, ParseError};
 use crate::freq::Frequency;
 use crate::recur::Recur;
+use crate::zone::{DefaultZone, Zone};
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+use core::str::FromStr;
 use nom::bytes::complete::{tag, take_while1};
 use nom::combinator::map;
 use nom::sequence::{preceded, terminated, tuple};
 use nom::Finish;
-use std::str::FromStr;
 
-#[derive(Debug)]
-pub struct RRule {
-    pub(crate) dt_start: DtStart,
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RRule<Z: Zone = DefaultZone> {
+    pub(crate) dt_start: DtStart<Z>,
     pub(crate) recur: Recur,
 }
 
@@ -33,11 +36,23 @@ pub enum RRuleVerifyError {
 
     #[error("invalid {0} value {1}")]
     InvalidValue(&'static str, i32),
-    #[error("[0] not allowed in FREQ {1}")]
+    #[error("{0} not allowed in FREQ {1}")]
     NotAllowedInFreq(&'static str, Frequency),
 }
 
-impl RRule {
+impl<Z: Zone> RRule<Z> {
+    pub fn new(dt_start: DtStart<Z>, recur: Recur) -> Self {
+        Self { dt_start, recur }
+    }
+
+    pub fn dt_start(&self) -> &DtStart<Z> {
+        &self.dt_start
+    }
+
+    pub fn recur(&self) -> &Recur {
+        &self.recur
+    }
+
     pub fn parse(i: &str) -> IResult<&str, Self> {
         map(
             tuple((
@@ -48,21 +63,36 @@ impl RRule {
         )(i)
     }
 
+    /// Normalize the rule, see [`Recur::normalize`]
+    pub fn normalize(&mut self) {
+        self.recur.normalize();
+    }
+
     pub fn verify(&self, strict: bool) -> Result<(), RRuleVerifyError> {
+        match self.verify_all(strict).into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Like [`RRule::verify`] but collects every error instead of returning the first
+    pub fn verify_all(&self, strict: bool) -> Vec<RRuleVerifyError> {
+        let mut errors = vec![];
+
         if let Some(interval) = self.recur.interval {
             if interval == 0 {
-                return Err(RRuleVerifyError::InvalidInterval(0));
+                errors.push(RRuleVerifyError::InvalidInterval(0));
             }
         }
 
         if let Some(until) = self.recur.until {
             //  The value of the UNTIL rule part MUST have the same value type as the "DTSTART" property.
             if until.is_date() != self.dt_start.0.dt.is_date() {
-                return Err(RRuleVerifyError::UntilInvalidValueType);
+                errors.push(RRuleVerifyError::UntilInvalidValueType);
             }
 
             let dt_start_is_local = matches!(self.dt_start.0.dt, Dt::DateTimeLocal(_));
-            let dt_start_has_tzid = self.dt_start.0.tz.is_none();
+            let dt_start_has_tzid = self.dt_start.0.tz.is_some();
             let dt_start_is_utc = matches!(self.dt_start.0.dt, Dt::DateTimeUtc(_));
 
             let until_is_local = matches!(until, Dt::DateTimeLocal(_));
@@ -71,13 +101,13 @@ impl RRule {
             // Furthermore, if the "DTSTART" property is specified as a date with local time,
             // then the UNTIL rule part MUST also be specified as a date with local time.
             if dt_start_is_local && !dt_start_has_tzid && !until_is_local {
-                return Err(RRuleVerifyError::UntilNotLocal);
+                errors.push(RRuleVerifyError::UntilNotLocal);
             }
 
             // If the "DTSTART" property is specified as a date with UTC time or a date with local time and time zone
             // reference, then the UNTIL rule part MUST be specified as a date with UTC time.
             if (dt_start_is_utc || (dt_start_is_local && dt_start_has_tzid)) && !until_is_utc {
-                return Err(RRuleVerifyError::UntilNotUtc);
+                errors.push(RRuleVerifyError::UntilNotUtc);
             }
         }
 
@@ -86,25 +116,31 @@ impl RRule {
         if strict
             && self.dt_start.0.dt.is_date()
             && (!self.recur.by_second.is_empty()
-                || self.recur.by_minute.is_empty()
-                || self.recur.by_hour.is_empty())
+                || !self.recur.by_minute.is_empty()
+                || !self.recur.by_hour.is_empty())
         {
-            return Err(RRuleVerifyError::StrictByXNotAllowedInDateType);
+            errors.push(RRuleVerifyError::StrictByXNotAllowedInDateType);
+        }
+
+        // BYSECOND allows 60 for leap seconds
+        for (name, values, max) in [
+            ("BYSECOND", &self.recur.by_second, 60),
+            ("BYMINUTE", &self.recur.by_minute, 59),
+            ("BYHOUR", &self.recur.by_hour, 23),
+        ] {
+            for value in values {
+                if *value > max {
+                    errors.push(RRuleVerifyError::InvalidValue(name, *value as i32));
+                }
+            }
         }
 
         let mut by_day_offset_specified = false;
 
         for by_day in &self.recur.by_day {
             if let ByDay::Nth(_, nth) = by_day {
-                if !matches!(self.recur.freq, Frequency::Yearly | Frequency::Monthly) {
-                    return Err(RRuleVerifyError::NotAllowedInFreq(
-                        "BDAY nth",
-                        self.recur.freq,
-                    ));
-                }
-
                 if !matches!(nth, 1..=53 | -53..=-1) {
-                    return Err(RRuleVerifyError::InvalidValue("BYDAY nth", *nth));
+                    errors.push(RRuleVerifyError::InvalidValue("BYDAY nth", *nth));
                 }
 
                 by_day_offset_specified = true;
@@ -116,7 +152,7 @@ impl RRule {
         if by_day_offset_specified
             && !matches!(self.recur.freq, Frequency::Monthly | Frequency::Yearly)
         {
-            return Err(RRuleVerifyError::NotAllowedInFreq(
+            errors.push(RRuleVerifyError::NotAllowedInFreq(
                 "BYDAY offset",
                 self.recur.freq,
             ));
@@ -128,18 +164,18 @@ impl RRule {
             && matches!(self.recur.freq, Frequency::Yearly)
             && !self.recur.by_week_no.is_empty()
         {
-            return Err(RRuleVerifyError::ByDayOffSetNotAllowedWithFreqYearlyAndByWeekNo);
+            errors.push(RRuleVerifyError::ByDayOffSetNotAllowedWithFreqYearlyAndByWeekNo);
         }
 
         // BYMONTHDAY
         for offset in &self.recur.by_month_day {
             if !matches!(offset, 1..=31 | -31..=-1) {
-                return Err(RRuleVerifyError::InvalidValue("BYMONTHDAY", *offset));
+                errors.push(RRuleVerifyError::InvalidValue("BYMONTHDAY", *offset));
             }
         }
 
         if matches!(self.recur.freq, Frequency::Weekly) && !self.recur.by_month_day.is_empty() {
-            return Err(RRuleVerifyError::NotAllowedInFreq(
+            errors.push(RRuleVerifyError::NotAllowedInFreq(
                 "BYMONTHDAY",
                 Frequency::Weekly,
             ));
@@ -148,7 +184,7 @@ impl RRule {
         // BYYEARDAY
         for offset in &self.recur.by_year_day {
             if !matches!(offset, 1..=366 | -366..=-1) {
-                return Err(RRuleVerifyError::InvalidValue("BYYEARDAY", *offset));
+                errors.push(RRuleVerifyError::InvalidValue("BYYEARDAY", *offset));
             }
         }
 
@@ -158,7 +194,7 @@ impl RRule {
             Frequency::Daily | Frequency::Weekly | Frequency::Monthly
         ) && !self.recur.by_year_day.is_empty()
         {
-            return Err(RRuleVerifyError::NotAllowedInFreq(
+            errors.push(RRuleVerifyError::NotAllowedInFreq(
                 "BYYEARDAY",
                 self.recur.freq,
             ));
@@ -167,13 +203,13 @@ impl RRule {
         // BYWEEKNO
         for week_no in &self.recur.by_week_no {
             if !matches!(week_no, 1..=53 | -53..=-1) {
-                return Err(RRuleVerifyError::InvalidValue("BYWEEKNO", *week_no));
+                errors.push(RRuleVerifyError::InvalidValue("BYWEEKNO", *week_no));
             }
         }
 
         // This rule part MUST NOT be used when the FREQ rule part is set to anything other than YEARLY.
         if !matches!(self.recur.freq, Frequency::Yearly) && !self.recur.by_week_no.is_empty() {
-            return Err(RRuleVerifyError::NotAllowedInFreq(
+            errors.push(RRuleVerifyError::NotAllowedInFreq(
                 "BYWEEKNO",
                 self.recur.freq,
             ));
@@ -181,18 +217,18 @@ impl RRule {
 
         for month in &self.recur.by_month {
             if !matches!(month, 1..=12) {
-                return Err(RRuleVerifyError::InvalidValue("BYMONTH", *month as i32));
+                errors.push(RRuleVerifyError::InvalidValue("BYMONTH", *month as i32));
             }
         }
 
         // BYSETPOS
         for offset in &self.recur.by_set_pos {
             if !matches!(offset, 1..=366 | -366..=-1) {
-                return Err(RRuleVerifyError::InvalidValue("BYSETPOS", *offset));
+                errors.push(RRuleVerifyError::InvalidValue("BYSETPOS", *offset));
             }
         }
 
-        Ok(())
+        errors
     }
 }
 
@@ -205,11 +241,11 @@ pub enum RRuleFromStrError {
     LeftOver(String),
 }
 
-impl FromStr for RRule {
+impl<Z: Zone> FromStr for RRule<Z> {
     type Err = RRuleFromStrError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (rem, rrule) = Self::parse(s).finish()?;
+        let (rem, rrule) = Self::parse(s).finish().map_err(|e| e.locate(s))?;
 
         if rem.is_empty() {
             Ok(rrule)
@@ -218,3 +254,27 @@ impl FromStr for RRule {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_until() {
+        let verify = |s: &str| s.parse::<RRule>().unwrap().verify(false);
+
+        assert!(verify(
+            "DTSTART;TZID=UTC:20220103T090000\nRRULE:FREQ=DAILY;UNTIL=20220110T090000Z"
+        )
+        .is_ok());
+        assert!(matches!(
+            verify("DTSTART;TZID=UTC:20220103T090000\nRRULE:FREQ=DAILY;UNTIL=20220110T090000"),
+            Err(RRuleVerifyError::UntilNotUtc)
+        ));
+        assert!(verify("DTSTART:20220103T090000\nRRULE:FREQ=DAILY;UNTIL=20220110T090000").is_ok());
+        assert!(matches!(
+            verify("DTSTART:20220103T090000\nRRULE:FREQ=DAILY;UNTIL=20220110T090000Z"),
+            Err(RRuleVerifyError::UntilNotLocal)
+        ));
+    }
+}
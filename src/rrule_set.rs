@@ -0,0 +1,266 @@
+use crate::dt::Dt;
+use crate::dt_prop::{DtProperty, DtStart, ExDate, RDate};
+use crate::error::{IResult, ParseError};
+use crate::iter::RRuleSetIter;
+use crate::recur::Recur;
+use crate::rrule::RRule;
+use crate::zone::{DefaultZone, Zone};
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use core::str::FromStr;
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_while1};
+use nom::character::complete::char;
+use nom::combinator::{all_consuming, map, map_parser, map_res};
+use nom::multi::{many0, separated_list1};
+use nom::sequence::{pair, preceded};
+use nom::Finish;
+
+/// Recurrence set of [RFC5545#3.8.5], consisting of DTSTART, RRULEs, EXRULEs, RDATEs and EXDATEs
+///
+/// [RFC5545#3.8.5]: https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5
+#[derive(Debug, Clone)]
+pub struct RRuleSet<Z: Zone = DefaultZone> {
+    pub dt_start: DtStart<Z>,
+    pub rrules: Vec<Recur>,
+    pub exrules: Vec<Recur>,
+    pub rdates: Vec<RDate<Z>>,
+    pub exdates: Vec<ExDate<Z>>,
+}
+
+enum Line<Z: Zone> {
+    DtStart(DtStart<Z>),
+    RRule(Recur),
+    ExRule(Recur),
+    RDate(Vec<RDate<Z>>),
+    ExDate(Vec<ExDate<Z>>),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RRuleSetParseError {
+    #[error("missing DTSTART")]
+    MissingDtStart,
+    #[error("duplicate DTSTART")]
+    DuplicateDtStart,
+}
+
+/// Parse the remaining values of a property with a list of values, e.g. `RDATE:20220101,20220102`
+fn parse_more_values(i: &str) -> IResult<&str, Vec<Dt>> {
+    many0(preceded(char(','), Dt::parse))(i)
+}
+
+fn with_values<Z: Zone>(
+    first: DtProperty<Z>,
+    more: Vec<Dt>,
+) -> impl Iterator<Item = DtProperty<Z>> {
+    core::iter::once(first).chain(more.into_iter().map(move |dt| DtProperty { dt, ..first }))
+}
+
+impl<Z: Zone> Line<Z> {
+    fn parse(i: &str) -> IResult<&str, Self> {
+        // RRULE values are not delimited by line endings, so each line is parsed on its own
+        map_parser(
+            take_while1(|c| !matches!(c, '\r' | '\n')),
+            all_consuming(Self::parse_value),
+        )(i)
+    }
+
+    fn parse_value(i: &str) -> IResult<&str, Self> {
+        alt((
+            map(DtStart::parse, Self::DtStart),
+            map(preceded(tag("RRULE:"), Recur::parse), Self::RRule),
+            map(preceded(tag("EXRULE:"), Recur::parse), Self::ExRule),
+            map(pair(RDate::parse, parse_more_values), |(first, more)| {
+                Self::RDate(with_values(first.0, more).map(RDate).collect())
+            }),
+            map(pair(ExDate::parse, parse_more_values), |(first, more)| {
+                Self::ExDate(with_values(first.0, more).map(ExDate).collect())
+            }),
+        ))(i)
+    }
+}
+
+impl<Z: Zone> RRuleSet<Z> {
+    pub fn new(dt_start: DtStart<Z>) -> Self {
+        Self {
+            dt_start,
+            rrules: vec![],
+            exrules: vec![],
+            rdates: vec![],
+            exdates: vec![],
+        }
+    }
+
+    pub fn parse(i: &str) -> IResult<&str, Self> {
+        map_res(
+            separated_list1(take_while1(|c| matches!(c, '\r' | '\n')), Line::parse),
+            |lines| -> Result<Self, RRuleSetParseError> {
+                let mut dt_start = None;
+                let mut rrules = vec![];
+                let mut exrules = vec![];
+                let mut rdates = vec![];
+                let mut exdates = vec![];
+
+                for line in lines {
+                    match line {
+                        Line::DtStart(d) => {
+                            if dt_start.replace(d).is_some() {
+                                return Err(RRuleSetParseError::DuplicateDtStart);
+                            }
+                        }
+                        Line::RRule(recur) => rrules.push(recur),
+                        Line::ExRule(recur) => exrules.push(recur),
+                        Line::RDate(dates) => rdates.extend(dates),
+                        Line::ExDate(dates) => exdates.extend(dates),
+                    }
+                }
+
+                Ok(Self {
+                    dt_start: dt_start.ok_or(RRuleSetParseError::MissingDtStart)?,
+                    rrules,
+                    exrules,
+                    rdates,
+                    exdates,
+                })
+            },
+        )(i)
+    }
+
+    pub fn iter(&self) -> RRuleSetIter<Z> {
+        RRuleSetIter::new(self)
+    }
+}
+
+impl<Z: Zone> From<RRule<Z>> for RRuleSet<Z> {
+    fn from(rrule: RRule<Z>) -> Self {
+        Self {
+            rrules: vec![rrule.recur],
+            ..Self::new(rrule.dt_start)
+        }
+    }
+}
+
+impl<Z: Zone> fmt::Display for RRuleSet<Z> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DTSTART{}", self.dt_start.0)?;
+
+        for recur in &self.rrules {
+            write!(f, "\nRRULE:{}", recur)?;
+        }
+
+        for recur in &self.exrules {
+            write!(f, "\nEXRULE:{}", recur)?;
+        }
+
+        for rdate in &self.rdates {
+            write!(f, "\nRDATE{}", rdate.0)?;
+        }
+
+        for exdate in &self.exdates {
+            write!(f, "\nEXDATE{}", exdate.0)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RRuleSetFromStrError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("some input was not consumed: '{0}'")]
+    LeftOver(String),
+}
+
+impl<Z: Zone> FromStr for RRuleSet<Z> {
+    type Err = RRuleSetFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (rem, set) = Self::parse(s.trim_end())
+            .finish()
+            .map_err(|e| e.locate(s))?;
+
+        if rem.is_empty() {
+            Ok(set)
+        } else {
+            Err(RRuleSetFromStrError::LeftOver(rem.into()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iter::RRuleIterYield;
+    use alloc::string::ToString;
+
+    #[test]
+    fn iter() {
+        let set: RRuleSet = "DTSTART:20220103T090000\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO,WE\n\
+            EXRULE:FREQ=MONTHLY;BYDAY=1MO\n\
+            RDATE:20220104T120000,20220105T090000\n\
+            EXDATE:20220112T090000"
+            .parse()
+            .unwrap();
+
+        let dates: Vec<_> = set
+            .iter()
+            .take(5)
+            .map(|yielded| match yielded {
+                RRuleIterYield::DateTimeLocal(datetime) => datetime.to_string(),
+                RRuleIterYield::DateTimeTz(datetime) => datetime.to_string(),
+            })
+            .collect();
+
+        assert_eq!(
+            dates,
+            [
+                "2022-01-04 12:00:00",
+                "2022-01-05 09:00:00",
+                "2022-01-10 09:00:00",
+                "2022-01-17 09:00:00",
+                "2022-01-19 09:00:00",
+            ]
+        );
+
+        assert_eq!(
+            set.to_string(),
+            "DTSTART:20220103T090000\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO,WE\n\
+            EXRULE:FREQ=MONTHLY;BYDAY=1MO\n\
+            RDATE:20220104T120000\n\
+            RDATE:20220105T090000\n\
+            EXDATE:20220112T090000"
+        );
+    }
+
+    #[test]
+    fn excluded_entirely() {
+        let set: RRuleSet = "DTSTART:20220103T090000\n\
+            RRULE:FREQ=DAILY\n\
+            EXRULE:FREQ=DAILY"
+            .parse()
+            .unwrap();
+
+        assert!(set.iter().next().is_none());
+
+        // Occurrences after the excluded weeks and the RDATE are still found
+        let set: RRuleSet = "DTSTART:20220103T090000\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO,TU\n\
+            EXRULE:FREQ=WEEKLY;BYDAY=MO\n\
+            RDATE:20220201T090000\n\
+            EXDATE:20220104T090000,20220111T090000"
+            .parse()
+            .unwrap();
+
+        let dates: Vec<_> = set
+            .iter()
+            .take(2)
+            .map(|yielded| yielded.local().to_string())
+            .collect();
+
+        assert_eq!(dates, ["2022-01-18 09:00:00", "2022-01-25 09:00:00"]);
+    }
+}
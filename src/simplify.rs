@@ -0,0 +1,286 @@
+use crate::byday::ByDay;
+use crate::dt_prop::DtStart;
+use crate::freq::Frequency;
+use crate::iter::{until_sort_key, RRuleIter};
+use crate::recur::Recur;
+use crate::rrule::RRule;
+use crate::rrule_set::RRuleSet;
+use alloc::{vec, vec::Vec};
+use chrono::{Datelike, Timelike};
+
+impl Recur {
+    /// Rewrite the rule into a shorter form with the same occurrences
+    ///
+    /// Besides [normalizing](Recur::normalize) the rule this removes parts which are implied by
+    /// DTSTART or do not restrict the occurrences, e.g. a `BYMONTH` with all 12 months, and
+    /// replaces a `WEEKLY` rule on all weekdays with a `DAILY` one.
+    pub fn simplify(&mut self, dt_start: &DtStart) {
+        self.normalize();
+
+        let start = dt_start.0.to_datetime().naive_local();
+        let interval = self.interval.unwrap_or(1);
+
+        // A single occurrence is not restricted by UNTIL, unless UNTIL comes before it
+        if let (Some(1), Some(until)) = (self.count, self.until) {
+            let unbounded = RRule::new(
+                *dt_start,
+                Recur {
+                    until: None,
+                    ..self.clone()
+                },
+            );
+
+            let until_first = RRuleIter::new(&unbounded)
+                .next()
+                .is_some_and(|first| first.sort_key() <= until_sort_key(until, dt_start.0.tz));
+
+            if until_first {
+                self.until = None;
+            }
+        }
+
+        let all_weekdays = self.by_day.len() == 7
+            && self
+                .by_day
+                .iter()
+                .all(|by_day| matches!(by_day, ByDay::All(_)));
+
+        if all_weekdays {
+            match self.freq {
+                Frequency::Weekly if interval == 1 && self.by_set_pos.is_empty() => {
+                    self.freq = Frequency::Daily;
+                    self.by_day.clear();
+                }
+                Frequency::Secondly
+                | Frequency::Minutely
+                | Frequency::Hourly
+                | Frequency::Daily => self.by_day.clear(),
+                Frequency::Monthly | Frequency::Yearly
+                    if !self.by_month_day.is_empty() || !self.by_year_day.is_empty() =>
+                {
+                    self.by_day.clear()
+                }
+                _ => {}
+            }
+        }
+
+        if self.by_month == (1..=12).collect::<Vec<_>>() {
+            let nth = self
+                .by_day
+                .iter()
+                .any(|by_day| matches!(by_day, ByDay::Nth(..)));
+
+            let expands_months = self.freq == Frequency::Yearly
+                && self.by_day.is_empty()
+                && self.by_month_day.is_empty()
+                && self.by_year_day.is_empty()
+                && self.by_week_no.is_empty();
+
+            if expands_months {
+                // Every month of every year
+                if interval == 1 && self.by_set_pos.is_empty() {
+                    self.freq = Frequency::Monthly;
+                    self.by_month.clear();
+                }
+            } else if !(self.freq == Frequency::Yearly && nth) {
+                self.by_month.clear();
+            }
+        }
+
+        // Time parts with a single value implied by DTSTART
+        if self.freq != Frequency::Secondly && self.by_second == [start.second()] {
+            self.by_second.clear();
+        }
+
+        if !matches!(self.freq, Frequency::Secondly | Frequency::Minutely)
+            && self.by_minute == [start.minute()]
+        {
+            self.by_minute.clear();
+        }
+
+        if !self.freq.is_sub_daily() && self.by_hour == [start.hour()] {
+            self.by_hour.clear();
+        }
+
+        // Date parts with a single value implied by DTSTART
+        let no_day_parts =
+            self.by_day.is_empty() && self.by_year_day.is_empty() && self.by_week_no.is_empty();
+
+        match self.freq {
+            Frequency::Monthly if no_day_parts && self.by_month_day == [start.day() as i32] => {
+                self.by_month_day.clear();
+            }
+            Frequency::Yearly
+                if no_day_parts
+                    && self.by_month == [start.month()]
+                    && (self.by_month_day.is_empty()
+                        || self.by_month_day == [start.day() as i32]) =>
+            {
+                self.by_month.clear();
+                self.by_month_day.clear();
+            }
+            _ => {}
+        }
+
+        if !(self.freq == Frequency::Weekly && interval > 1) && self.by_week_no.is_empty() {
+            self.week_start = None;
+        }
+    }
+}
+
+impl RRule {
+    /// Simplify the rule, see [`Recur::simplify`]
+    pub fn simplify(&mut self) {
+        self.recur.simplify(&self.dt_start);
+    }
+}
+
+impl RRuleSet {
+    /// Simplify all rules of the set and merge rules which differ in a single `BYxxx` part
+    ///
+    /// Rules are only merged if neither has COUNT or BYSETPOS, which would no longer apply to
+    /// the occurrences of each rule alone.
+    pub fn simplify(&mut self) {
+        for recur in self.rrules.iter_mut().chain(&mut self.exrules) {
+            recur.simplify(&self.dt_start);
+        }
+
+        merge(&mut self.rrules);
+        merge(&mut self.exrules);
+    }
+}
+
+fn merge(rules: &mut Vec<Recur>) {
+    let mut merged: Vec<Recur> = vec![];
+
+    'rules: for recur in rules.drain(..) {
+        for other in &mut merged {
+            if *other == recur || merge_into(other, &recur) {
+                continue 'rules;
+            }
+        }
+
+        merged.push(recur);
+    }
+
+    *rules = merged;
+}
+
+/// Merge `recur` into `into` if the union of their occurrences is a single rule
+fn merge_into(into: &mut Recur, recur: &Recur) -> bool {
+    if into.count.is_some()
+        || recur.count.is_some()
+        || !into.by_set_pos.is_empty()
+        || !recur.by_set_pos.is_empty()
+    {
+        return false;
+    }
+
+    // Compare the rules with one part taken from the other rule each
+    macro_rules! try_part {
+        ($($part:ident),*) => {
+            $(
+                if !into.$part.is_empty() && !recur.$part.is_empty() {
+                    let mut candidate = into.clone();
+                    candidate.$part = recur.$part.clone();
+
+                    if candidate == *recur {
+                        into.$part.extend_from_slice(&recur.$part);
+                        into.normalize();
+                        return true;
+                    }
+                }
+            )*
+        };
+    }
+
+    try_part!(
+        by_second,
+        by_minute,
+        by_hour,
+        by_day,
+        by_month_day,
+        by_year_day,
+        by_week_no,
+        by_month
+    );
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equivalence::Equivalence;
+    use alloc::format;
+    use alloc::string::{String, ToString};
+
+    fn simplify(recur: &str) -> String {
+        let s = format!("DTSTART:20220103T090000\nRRULE:{recur}");
+        let original: RRule = s.parse().unwrap();
+        let mut simplified: RRule = s.parse().unwrap();
+
+        simplified.simplify();
+
+        // Rules with calendar parts only repeat after 400 years, compare their start only
+        assert!(!matches!(
+            original.equivalent_within(&simplified, 5000),
+            Equivalence::Different(_)
+        ));
+
+        simplified.recur.to_string()
+    }
+
+    #[test]
+    fn redundant_parts() {
+        assert_eq!(
+            simplify("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR,SA,SU;BYHOUR=9"),
+            "FREQ=DAILY"
+        );
+        assert_eq!(
+            simplify("FREQ=DAILY;BYMONTH=1,2,3,4,5,6,7,8,9,10,11,12;BYMINUTE=0"),
+            "FREQ=DAILY"
+        );
+        assert_eq!(
+            simplify("FREQ=YEARLY;BYMONTH=1,2,3,4,5,6,7,8,9,10,11,12"),
+            "FREQ=MONTHLY"
+        );
+        assert_eq!(
+            simplify("FREQ=DAILY;COUNT=1;UNTIL=20220201T000000"),
+            "FREQ=DAILY;COUNT=1"
+        );
+        // DTSTART does not match the rule, its only occurrence comes after UNTIL
+        assert_eq!(
+            simplify("FREQ=WEEKLY;BYDAY=FR;COUNT=1;UNTIL=20220104T000000"),
+            "FREQ=WEEKLY;UNTIL=20220104T000000;COUNT=1;BYDAY=FR"
+        );
+        assert_eq!(
+            simplify("FREQ=YEARLY;BYMONTH=1;BYMONTHDAY=3"),
+            "FREQ=YEARLY"
+        );
+
+        // Nth weekdays per month are not the same as per year
+        assert_eq!(
+            simplify("FREQ=YEARLY;BYMONTH=1,2,3,4,5,6,7,8,9,10,11,12;BYDAY=1MO"),
+            "FREQ=YEARLY;BYDAY=1MO;BYMONTH=1,2,3,4,5,6,7,8,9,10,11,12"
+        );
+    }
+
+    #[test]
+    fn merge_rules() {
+        let mut set: RRuleSet = "DTSTART:20220103T090000\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO\n\
+            RRULE:FREQ=WEEKLY;BYDAY=WE;WKST=MO\n\
+            RRULE:FREQ=WEEKLY;BYDAY=FR;COUNT=3"
+            .parse()
+            .unwrap();
+
+        set.simplify();
+
+        let rules: Vec<_> = set.rrules.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            rules,
+            ["FREQ=WEEKLY;BYDAY=MO,WE", "FREQ=WEEKLY;COUNT=3;BYDAY=FR"]
+        );
+    }
+}
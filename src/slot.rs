@@ -0,0 +1,252 @@
+use crate::dt_prop::local_datetime_with_tz;
+use crate::freebusy::{FreeBusy, Period};
+use crate::interval::EventEnd;
+use crate::rrule_set::RRuleSet;
+use crate::weekday::Weekday;
+use crate::zone::{DefaultZone, Zone};
+use alloc::{vec, vec::Vec};
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, NaiveTime, Timelike, Utc};
+
+/// Schedule of one participant of a meeting, and when they are willing to meet
+#[derive(Debug, Clone)]
+pub struct Participant<'a, Z: Zone = DefaultZone> {
+    /// Events the participant is busy with
+    pub events: Vec<(&'a RRuleSet<Z>, &'a EventEnd<Z>)>,
+    /// Timezone of the working hours
+    pub tz: Z,
+    /// Working hours, ending on the next day if `latest` is not after `earliest`
+    pub earliest: NaiveTime,
+    pub latest: NaiveTime,
+    /// Days the working hours start on
+    pub weekdays: Vec<Weekday>,
+}
+
+impl<'a, Z: Zone> Participant<'a, Z> {
+    /// Participant working from 9:00 to 17:00, Monday to Friday
+    pub fn new(events: Vec<(&'a RRuleSet<Z>, &'a EventEnd<Z>)>, tz: Z) -> Self {
+        Self {
+            events,
+            tz,
+            earliest: NaiveTime::from_hms_opt(9, 0, 0).expect("valid time"),
+            latest: NaiveTime::from_hms_opt(17, 0, 0).expect("valid time"),
+            weekdays: vec![
+                Weekday::Monday,
+                Weekday::Tuesday,
+                Weekday::Wednesday,
+                Weekday::Thursday,
+                Weekday::Friday,
+            ],
+        }
+    }
+
+    /// Periods within `start` to `end` in which the participant is working and free
+    fn available(&self, start: NaiveDateTime, end: NaiveDateTime) -> Vec<Period> {
+        let free = FreeBusy::new(self.events.iter().copied(), self.tz, start, end).free;
+        intersect(&free, &self.working_hours(start, end))
+    }
+
+    /// Working hours overlapping `start` to `end`, in UTC
+    fn working_hours(&self, start: NaiveDateTime, end: NaiveDateTime) -> Vec<Period> {
+        let local_date =
+            |datetime: NaiveDateTime| datetime.and_utc().with_timezone(&self.tz).date_naive();
+
+        let utc = |datetime: NaiveDateTime| local_datetime_with_tz(datetime, self.tz).naive_utc();
+
+        // Working hours of the previous day may last past midnight
+        let mut date = local_date(start).pred_opt().unwrap_or(local_date(start));
+        let last = local_date(end);
+        let mut periods = vec![];
+
+        while date <= last {
+            if self
+                .weekdays
+                .iter()
+                .any(|weekday| *weekday == date.weekday())
+            {
+                let from = date.and_time(self.earliest);
+                let mut to = date.and_time(self.latest);
+
+                if to <= from {
+                    to += Duration::days(1);
+                }
+
+                let (from, to) = (utc(from).max(start), utc(to).min(end));
+
+                if from < to {
+                    periods.push(Period {
+                        start: from,
+                        end: to,
+                    });
+                }
+            }
+
+            match date.succ_opt() {
+                Some(next) => date = next,
+                None => break,
+            }
+        }
+
+        periods
+    }
+}
+
+/// Search for meeting slots all participants can attend
+#[derive(Debug, Clone, Copy)]
+pub struct SlotSearch {
+    /// Length of the slots
+    pub length: Duration,
+    /// Time of the search
+    pub now: DateTime<Utc>,
+    /// Minimum time from `now` to the start of a slot
+    pub notice: Duration,
+    /// Maximum time from `now` to the end of a slot
+    pub horizon: Duration,
+    /// Slots start at multiples of this, counted from the Unix epoch
+    pub granularity: Duration,
+}
+
+impl SlotSearch {
+    /// Search for slots within the next four weeks, starting on the quarter hour
+    pub fn new(length: Duration, now: DateTime<Utc>) -> Self {
+        Self {
+            length,
+            now,
+            notice: Duration::zero(),
+            horizon: Duration::weeks(4),
+            granularity: Duration::minutes(15),
+        }
+    }
+
+    /// First `n` slots in which all participants are available, as UTC periods
+    ///
+    /// Floating events of a participant are taken in the timezone of their working hours. Slots do not overlap each other.
+    pub fn find<Z: Zone>(&self, participants: &[Participant<Z>], n: usize) -> Vec<Period> {
+        let now = self.now.naive_utc();
+        let (start, end) = (now + self.notice, now + self.horizon);
+
+        if start >= end || self.length <= Duration::zero() {
+            return vec![];
+        }
+
+        let mut available = vec![Period { start, end }];
+
+        for participant in participants {
+            if available.is_empty() {
+                break;
+            }
+
+            available = intersect(&available, &participant.available(start, end));
+        }
+
+        let mut slots = vec![];
+
+        for period in available {
+            let mut slot_start = self.align(period.start);
+
+            while slots.len() < n && slot_start + self.length <= period.end {
+                slots.push(Period {
+                    start: slot_start,
+                    end: slot_start + self.length,
+                });
+
+                slot_start = self.align(slot_start + self.length);
+            }
+        }
+
+        slots
+    }
+
+    /// Next multiple of the granularity, at or after `datetime`
+    fn align(&self, datetime: NaiveDateTime) -> NaiveDateTime {
+        let granularity = self.granularity.num_seconds();
+
+        if granularity <= 0 {
+            return datetime;
+        }
+
+        let remainder = datetime.and_utc().timestamp().rem_euclid(granularity);
+
+        if remainder == 0 && datetime.nanosecond() == 0 {
+            datetime
+        } else {
+            datetime.with_nanosecond(0).expect("valid time")
+                + Duration::seconds(granularity - remainder)
+        }
+    }
+}
+
+/// Intersection of two sorted lists of disjoint periods
+fn intersect(a: &[Period], b: &[Period]) -> Vec<Period> {
+    let (mut i, mut j) = (0, 0);
+    let mut periods = vec![];
+
+    while i < a.len() && j < b.len() {
+        let start = a[i].start.max(b[j].start);
+        let end = a[i].end.min(b[j].end);
+
+        if start < end {
+            periods.push(Period { start, end });
+        }
+
+        if a[i].end < b[j].end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    periods
+}
+
+#[cfg(test)]
+#[cfg(feature = "chrono-tz")]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono_tz::Tz;
+
+    fn event(set: &str, end: &str) -> (RRuleSet, EventEnd) {
+        (set.parse().unwrap(), EventEnd::parse(end).unwrap().1)
+    }
+
+    #[test]
+    fn find_slots() {
+        let standup = event(
+            "DTSTART;TZID=Europe/Berlin:20220103T150000\nRRULE:FREQ=DAILY",
+            "DURATION:PT30M",
+        );
+        let weekly = event(
+            "DTSTART;TZID=America/New_York:20220103T100000\nRRULE:FREQ=WEEKLY",
+            "DURATION:PT1H",
+        );
+
+        let berlin = Participant::new(vec![(&standup.0, &standup.1)], Tz::Europe__Berlin);
+        let mut new_york = Participant::new(vec![(&weekly.0, &weekly.1)], Tz::America__New_York);
+
+        let mut search = SlotSearch::new(
+            Duration::hours(1),
+            Utc.with_ymd_and_hms(2022, 1, 3, 8, 0, 0).unwrap(),
+        );
+        search.notice = Duration::hours(1);
+
+        let starts = |participants: &[Participant]| -> Vec<String> {
+            search
+                .find(participants, 2)
+                .iter()
+                .map(|slot| slot.start.format("%a %H:%M").to_string())
+                .collect()
+        };
+
+        // Working hours overlap from 14:00 to 16:00 UTC, on Monday only 14:30 to 15:00 is free
+        assert_eq!(
+            starts(&[berlin.clone(), new_york.clone()]),
+            ["Tue 14:30", "Wed 14:30"]
+        );
+
+        new_york
+            .weekdays
+            .retain(|weekday| *weekday != Weekday::Tuesday);
+
+        assert_eq!(starts(&[berlin, new_york]), ["Wed 14:30", "Thu 14:30"]);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::dt::Dt;
+use crate::dt_prop::DtStart;
+use crate::iter::{RRuleIter, RRuleIterYield};
+use crate::rrule::RRule;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SplitError {
+    #[error("{0:?} is not an occurrence of the rule")]
+    NotAnOccurrence(RRuleIterYield),
+    #[error("cannot split at the first occurrence")]
+    FirstOccurrence,
+}
+
+impl RRule {
+    /// Split the rule into the occurrences before `at` and those from `at` on, e.g. to edit
+    /// "this and all following" occurrences
+    ///
+    /// The first rule ends with the occurrence before `at`, by reducing its COUNT or by an
+    /// UNTIL of the value type required for DTSTART, see [`RRuleVerifyError::UntilNotUtc`] and
+    /// [`RRuleVerifyError::UntilNotLocal`]. The second rule starts at `at` with the remaining
+    /// COUNT.
+    ///
+    /// [`RRuleVerifyError::UntilNotUtc`]: crate::rrule::RRuleVerifyError::UntilNotUtc
+    /// [`RRuleVerifyError::UntilNotLocal`]: crate::rrule::RRuleVerifyError::UntilNotLocal
+    pub fn split(&self, at: RRuleIterYield) -> Result<(RRule, RRule), SplitError> {
+        let key = at.sort_key();
+
+        let mut previous = None;
+
+        for (before, occurrence) in RRuleIter::new(self).enumerate() {
+            if occurrence.sort_key() >= key {
+                if occurrence.sort_key() > key {
+                    break;
+                }
+
+                let previous = previous.ok_or(SplitError::FirstOccurrence)?;
+                return Ok(self.split_after(previous, occurrence, before));
+            }
+
+            previous = Some(occurrence);
+        }
+
+        Err(SplitError::NotAnOccurrence(at))
+    }
+
+    fn split_after(
+        &self,
+        last: RRuleIterYield,
+        at: RRuleIterYield,
+        before: usize,
+    ) -> (RRule, RRule) {
+        let dt_start = self.dt_start.0;
+
+        let mut first = self.recur.clone();
+        let mut second = self.recur.clone();
+
+        match self.recur.count {
+            Some(count) => {
+                // Fewer occurrences than COUNT precede the split
+                let before = before as u32;
+
+                first.count = Some(before);
+                second.count = Some(count - before);
+            }
+            None => {
+                first.until = Some(match dt_start.dt {
+                    Dt::Date(_) => Dt::Date(last.local().date()),
+                    Dt::DateTimeLocal(_) if dt_start.tz.is_none() => {
+                        Dt::DateTimeLocal(last.local())
+                    }
+                    _ => Dt::DateTimeUtc(last.sort_key().and_utc()),
+                });
+            }
+        }
+
+        (
+            RRule::new(self.dt_start, first),
+            RRule::new(DtStart(dt_start.at(at)), second),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rrule_set::RRuleSet;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    fn split(rrule: &str, nth: usize) -> (RRule, RRule) {
+        let rrule: RRule = rrule.parse().unwrap();
+        let at = RRuleIter::new(&rrule).nth(nth).unwrap();
+        let (first, second) = rrule.split(at).unwrap();
+
+        first.verify(true).unwrap();
+        second.verify(true).unwrap();
+
+        // Both parts together have the same occurrences as the original rule
+        let occurrences = |rrule: &RRule| -> Vec<_> {
+            RRuleIter::new(rrule)
+                .take(20)
+                .map(|occurrence| occurrence.sort_key())
+                .collect()
+        };
+        let mut joined = occurrences(&first);
+        joined.extend(occurrences(&second));
+        joined.truncate(20);
+
+        assert_eq!(joined, occurrences(&rrule));
+
+        (first, second)
+    }
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn split_until() {
+        let (first, second) = split(
+            "DTSTART;TZID=Europe/Berlin:20220103T090000\nRRULE:FREQ=WEEKLY;BYDAY=MO,TH",
+            3,
+        );
+
+        assert_eq!(
+            first.recur.to_string(),
+            "FREQ=WEEKLY;UNTIL=20220110T080000Z;BYDAY=MO,TH"
+        );
+        assert_eq!(
+            RRuleSet::from(second).to_string(),
+            "DTSTART;TZID=Europe/Berlin:20220113T090000\nRRULE:FREQ=WEEKLY;BYDAY=MO,TH"
+        );
+
+        let (first, _) = split("DTSTART:20220103T090000\nRRULE:FREQ=DAILY", 2);
+        assert_eq!(first.recur.to_string(), "FREQ=DAILY;UNTIL=20220104T090000");
+
+        let (first, _) = split("DTSTART;VALUE=DATE:20220103\nRRULE:FREQ=DAILY", 2);
+        assert_eq!(first.recur.to_string(), "FREQ=DAILY;UNTIL=20220104");
+    }
+
+    #[test]
+    fn split_count() {
+        let (first, second) = split("DTSTART:20220103T090000Z\nRRULE:FREQ=DAILY;COUNT=5", 2);
+
+        assert_eq!(first.recur.to_string(), "FREQ=DAILY;COUNT=2");
+        assert_eq!(
+            RRuleSet::from(second).to_string(),
+            "DTSTART:20220105T090000Z\nRRULE:FREQ=DAILY;COUNT=3"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let rrule: RRule = "DTSTART:20220103T090000\nRRULE:FREQ=DAILY".parse().unwrap();
+        let first = RRuleIter::new(&rrule).next().unwrap();
+
+        assert!(matches!(
+            rrule.split(first),
+            Err(SplitError::FirstOccurrence)
+        ));
+
+        let not_an_occurrence =
+            RRuleIterYield::DateTimeLocal(first.local() + chrono::Duration::hours(1));
+        assert!(matches!(
+            rrule.split(not_an_occurrence),
+            Err(SplitError::NotAnOccurrence(_))
+        ));
+    }
+}
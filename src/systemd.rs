@@ -0,0 +1,803 @@
+use crate::byday::ByDay;
+use crate::dt::Dt;
+use crate::dt_prop::{local_datetime_with_tz, DtProperty, DtStart};
+use crate::error::{IResult, ParseError};
+use crate::freq::Frequency;
+use crate::recur::Recur;
+use crate::rrule::RRule;
+use crate::util::parse_u32;
+use crate::weekday::Weekday;
+use crate::zone::Zone;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike, Utc};
+use nom::branch::alt;
+use nom::bytes::complete::{tag, tag_no_case};
+use nom::character::complete::char;
+use nom::combinator::{all_consuming, map, opt, value};
+use nom::multi::separated_list1;
+use nom::sequence::{pair, preceded, tuple};
+use nom::Finish;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OnCalendarParseError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("value {1} out of range for {0}")]
+    OutOfRange(&'static str, u32),
+    #[error("unexpected '{0}'")]
+    Unexpected(String),
+    #[error("unknown timezone '{0}'")]
+    UnknownTimezone(String),
+    #[error("{0} cannot be expressed as RRULE")]
+    Unsupported(&'static str),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OnCalendarFormatError {
+    #[error("{0} cannot be expressed as OnCalendar")]
+    Unsupported(&'static str),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Component {
+    name: &'static str,
+    min: u32,
+    max: u32,
+}
+
+const YEAR: Component = Component {
+    name: "year",
+    min: 1970,
+    max: 2199,
+};
+
+const MONTH: Component = Component {
+    name: "month",
+    min: 1,
+    max: 12,
+};
+
+const DAY: Component = Component {
+    name: "day",
+    min: 1,
+    max: 31,
+};
+
+const HOUR: Component = Component {
+    name: "hour",
+    min: 0,
+    max: 23,
+};
+
+const MINUTE: Component = Component {
+    name: "minute",
+    min: 0,
+    max: 59,
+};
+
+const SECOND: Component = Component {
+    name: "second",
+    min: 0,
+    max: 59,
+};
+
+/// `*`, `a`, `a..b` with an optional repetition `/n`
+#[derive(Debug, Clone, Copy)]
+struct Item {
+    start: Option<u32>,
+    end: Option<u32>,
+    repeat: Option<u32>,
+}
+
+fn parse_item(i: &str) -> IResult<&str, Item> {
+    map(
+        pair(
+            alt((
+                map(char('*'), |_| (None, None)),
+                map(
+                    pair(parse_u32, opt(preceded(tag(".."), parse_u32))),
+                    |(start, end)| (Some(start), end),
+                ),
+            )),
+            opt(preceded(char('/'), parse_u32)),
+        ),
+        |((start, end), repeat)| Item { start, end, repeat },
+    )(i)
+}
+
+fn parse_component(i: &str) -> IResult<&str, Vec<Item>> {
+    separated_list1(char(','), parse_item)(i)
+}
+
+/// Expand a component into its values, `None` if it matches everything
+///
+/// Days counted `from_end` of the month repeat towards the last day, e.g. `~07/1` is the last
+/// seven days.
+fn expand(
+    component: Component,
+    items: &[Item],
+    from_end: bool,
+) -> Result<Option<Vec<u32>>, OnCalendarParseError> {
+    let check = |v: u32| {
+        if (component.min..=component.max).contains(&v) {
+            Ok(v)
+        } else {
+            Err(OnCalendarParseError::OutOfRange(component.name, v))
+        }
+    };
+
+    let mut values = vec![];
+
+    for item in items {
+        let repeat = item.repeat.unwrap_or(1).max(1) as usize;
+
+        match (item.start, item.end) {
+            (None, _) if repeat == 1 => return Ok(None),
+            (None, _) => values.extend((component.min..=component.max).step_by(repeat)),
+            (Some(start), Some(end)) => {
+                values.extend((check(start)?..=check(end)?).step_by(repeat))
+            }
+            // `a/n` repeats until the end of the range
+            (Some(start), None) if item.repeat.is_some() && from_end => {
+                values.extend((component.min..=check(start)?).rev().step_by(repeat))
+            }
+            (Some(start), None) if item.repeat.is_some() => {
+                values.extend((check(start)?..=component.max).step_by(repeat))
+            }
+            (Some(start), None) => values.push(check(start)?),
+        }
+    }
+
+    values.sort_unstable();
+    values.dedup();
+
+    Ok(Some(values))
+}
+
+fn parse_weekday(i: &str) -> IResult<&str, Weekday> {
+    alt((
+        value(
+            Weekday::Monday,
+            alt((tag_no_case("monday"), tag_no_case("mon"))),
+        ),
+        value(
+            Weekday::Tuesday,
+            alt((tag_no_case("tuesday"), tag_no_case("tue"))),
+        ),
+        value(
+            Weekday::Wednesday,
+            alt((tag_no_case("wednesday"), tag_no_case("wed"))),
+        ),
+        value(
+            Weekday::Thursday,
+            alt((tag_no_case("thursday"), tag_no_case("thu"))),
+        ),
+        value(
+            Weekday::Friday,
+            alt((tag_no_case("friday"), tag_no_case("fri"))),
+        ),
+        value(
+            Weekday::Saturday,
+            alt((tag_no_case("saturday"), tag_no_case("sat"))),
+        ),
+        value(
+            Weekday::Sunday,
+            alt((tag_no_case("sunday"), tag_no_case("sun"))),
+        ),
+    ))(i)
+}
+
+fn parse_weekdays(i: &str) -> IResult<&str, Vec<Weekday>> {
+    map(
+        separated_list1(
+            char(','),
+            pair(parse_weekday, opt(preceded(tag(".."), parse_weekday))),
+        ),
+        |ranges| {
+            let mut weekdays = vec![];
+
+            for (start, end) in ranges {
+                let end = end.unwrap_or(start);
+                let mut day = start as u32;
+
+                // ranges may wrap around the week, e.g. Sat..Mon
+                loop {
+                    weekdays.push(weekday_from_u32(day));
+
+                    if day == end as u32 {
+                        break;
+                    }
+
+                    day = (day + 1) % 7;
+                }
+            }
+
+            weekdays.sort_unstable();
+            weekdays.dedup();
+            weekdays
+        },
+    )(i)
+}
+
+struct Date {
+    year: Vec<Item>,
+    month: Vec<Item>,
+    day: Vec<Item>,
+    from_end: bool,
+}
+
+/// `[YYYY-]MM-DD` or `[YYYY-]MM~DD`
+fn parse_date(i: &str) -> IResult<&str, Date> {
+    let separator = |i| alt((value(false, char('-')), value(true, char('~'))))(i);
+
+    alt((
+        map(
+            tuple((
+                parse_component,
+                char('-'),
+                parse_component,
+                separator,
+                parse_component,
+            )),
+            |(year, _, month, from_end, day)| Date {
+                year,
+                month,
+                day,
+                from_end,
+            },
+        ),
+        map(
+            tuple((parse_component, separator, parse_component)),
+            |(month, from_end, day)| Date {
+                year: vec![Item {
+                    start: None,
+                    end: None,
+                    repeat: None,
+                }],
+                month,
+                day,
+                from_end,
+            },
+        ),
+    ))(i)
+}
+
+struct Time {
+    hour: Vec<Item>,
+    minute: Vec<Item>,
+    second: Option<Vec<Item>>,
+}
+
+/// `HH:MM[:SS]`
+fn parse_time(i: &str) -> IResult<&str, Time> {
+    map(
+        tuple((
+            parse_component,
+            preceded(char(':'), parse_component),
+            opt(preceded(char(':'), parse_component)),
+        )),
+        |(hour, minute, second)| Time {
+            hour,
+            minute,
+            second,
+        },
+    )(i)
+}
+
+fn weekday_from_u32(day: u32) -> Weekday {
+    match day % 7 {
+        0 => Weekday::Monday,
+        1 => Weekday::Tuesday,
+        2 => Weekday::Wednesday,
+        3 => Weekday::Thursday,
+        4 => Weekday::Friday,
+        5 => Weekday::Saturday,
+        _ => Weekday::Sunday,
+    }
+}
+
+fn expand_shorthand(spec: &str) -> Option<&'static str> {
+    Some(match spec.to_ascii_lowercase().as_str() {
+        "minutely" => "*-*-* *:*:00",
+        "hourly" => "*-*-* *:00:00",
+        "daily" => "*-*-* 00:00:00",
+        "weekly" => "Mon *-*-* 00:00:00",
+        "monthly" => "*-*-01 00:00:00",
+        "yearly" | "annually" => "*-01-01 00:00:00",
+        "quarterly" => "*-01,04,07,10-01 00:00:00",
+        "semiannually" => "*-01,07-01 00:00:00",
+        _ => return None,
+    })
+}
+
+impl<Z: Zone> RRule<Z> {
+    /// Convert a systemd `OnCalendar=` specification, e.g. `Mon..Fri *-*-* 09:00:00 Europe/Berlin`
+    ///
+    /// The rule starts on `start` or the first year the specification allows, whichever is later.
+    /// Without a timezone suffix the rule is floating.
+    pub fn from_on_calendar(spec: &str, start: NaiveDate) -> Result<Self, OnCalendarParseError> {
+        let spec = spec.trim();
+        let spec = expand_shorthand(spec).unwrap_or(spec);
+        let mut tokens = spec.split_whitespace().peekable();
+
+        let mut weekdays = vec![];
+        let mut date = None;
+        let mut time = None;
+
+        if let Some(token) = tokens.peek() {
+            if let Ok((_, parsed)) = all_consuming(parse_weekdays)(token) {
+                weekdays = parsed;
+                tokens.next();
+            }
+        }
+
+        if let Some(token) = tokens.peek() {
+            if !token.contains(':') && token.contains(['-', '~']) {
+                date = Some(all_consuming(parse_date)(token).finish()?.1);
+                tokens.next();
+            }
+        }
+
+        if let Some(token) = tokens.peek() {
+            if token.contains(':') {
+                time = Some(all_consuming(parse_time)(token).finish()?.1);
+                tokens.next();
+            }
+        }
+
+        let tz = match tokens.next() {
+            Some(tz) => Some(
+                Z::from_tzid(tz).ok_or_else(|| OnCalendarParseError::UnknownTimezone(tz.into()))?,
+            ),
+            None => None,
+        };
+
+        if let Some(rest) = tokens.next() {
+            return Err(OnCalendarParseError::Unexpected(rest.into()));
+        }
+
+        let zero = || {
+            vec![Item {
+                start: Some(0),
+                end: None,
+                repeat: None,
+            }]
+        };
+
+        let time = time.unwrap_or_else(|| Time {
+            hour: zero(),
+            minute: zero(),
+            second: None,
+        });
+        let hours = expand(HOUR, &time.hour, false)?;
+        let minutes = expand(MINUTE, &time.minute, false)?;
+        let seconds = expand(SECOND, &time.second.unwrap_or_else(zero), false)?;
+
+        let mut recur = Recur::new(if seconds.is_none() {
+            Frequency::Secondly
+        } else if minutes.is_none() {
+            Frequency::Minutely
+        } else if hours.is_none() {
+            Frequency::Hourly
+        } else {
+            Frequency::Daily
+        });
+
+        // Single values are carried by DTSTART, unless a finer component repeats and FREQ would
+        // step past them, e.g. the hour of `09:*:00`
+        let first = |values: &Option<Vec<u32>>| values.as_ref().map_or(0, |v| v[0]);
+        let time_of_day = NaiveTime::from_hms_opt(first(&hours), first(&minutes), first(&seconds))
+            .expect("validated time components");
+
+        let by_x = |values: Option<Vec<u32>>, finer_repeats: bool| match values {
+            Some(values) if values.len() > 1 || finer_repeats => values,
+            _ => vec![],
+        };
+
+        let (every_minute, every_second) = (minutes.is_none(), seconds.is_none());
+
+        recur.by_second = by_x(seconds, false);
+        recur.by_minute = by_x(minutes, every_second);
+        recur.by_hour = by_x(hours, every_minute || every_second);
+
+        recur.by_day = weekdays.into_iter().map(ByDay::All).collect();
+
+        let mut dt_start_date = start;
+
+        if let Some(date) = date {
+            recur.by_month = expand(MONTH, &date.month, false)?.unwrap_or_default();
+
+            if let Some(days) = expand(DAY, &date.day, date.from_end)? {
+                recur.by_month_day = days
+                    .into_iter()
+                    .map(|d| if date.from_end { -(d as i32) } else { d as i32 })
+                    .collect();
+            }
+
+            if let Some(years) = expand(YEAR, &date.year, false)? {
+                let (first, last) = (years[0], years[years.len() - 1]);
+
+                if (last - first) as usize + 1 != years.len() {
+                    return Err(OnCalendarParseError::Unsupported("non contiguous years"));
+                }
+
+                let first_day = NaiveDate::from_ymd_opt(first as i32, 1, 1).expect("valid year");
+                dt_start_date = dt_start_date.max(first_day);
+
+                let until = NaiveDate::from_ymd_opt(last as i32, 12, 31)
+                    .and_then(|date| date.and_hms_opt(23, 59, 59))
+                    .expect("valid year");
+
+                recur.until = Some(match tz {
+                    Some(tz) => {
+                        Dt::DateTimeUtc(local_datetime_with_tz(until, tz).with_timezone(&Utc))
+                    }
+                    None => Dt::DateTimeLocal(until),
+                });
+            }
+        }
+
+        recur.sort_and_dedup();
+
+        let dt_start = DtStart(DtProperty {
+            dt: Dt::DateTimeLocal(dt_start_date.and_time(time_of_day)),
+            tz,
+        });
+
+        Ok(RRule::new(dt_start, recur))
+    }
+
+    /// Convert to a systemd `OnCalendar=` specification where possible
+    ///
+    /// Parts not set by the rule are taken from DTSTART.
+    pub fn to_on_calendar(&self) -> Result<String, OnCalendarFormatError> {
+        let mut recur = self.recur.clone();
+        recur.sort_and_dedup();
+
+        let dt_start = self.dt_start.0.to_datetime().naive_local();
+
+        if recur.count.is_some() {
+            return Err(OnCalendarFormatError::Unsupported("COUNT"));
+        }
+
+        if recur.until.is_some() {
+            return Err(OnCalendarFormatError::Unsupported("UNTIL"));
+        }
+
+        if !recur.by_year_day.is_empty() {
+            return Err(OnCalendarFormatError::Unsupported("BYYEARDAY"));
+        }
+
+        if !recur.by_week_no.is_empty() {
+            return Err(OnCalendarFormatError::Unsupported("BYWEEKNO"));
+        }
+
+        if !recur.by_set_pos.is_empty() {
+            return Err(OnCalendarFormatError::Unsupported("BYSETPOS"));
+        }
+
+        let (minute_repeat, hour_repeat) = match (recur.freq, recur.interval.unwrap_or(1)) {
+            (_, 1) => (None, None),
+            (Frequency::Minutely, n) if 60 % n == 0 && recur.by_minute.is_empty() => {
+                (Some(n), None)
+            }
+            (Frequency::Hourly, n) if 24 % n == 0 && recur.by_hour.is_empty() => (None, Some(n)),
+            _ => return Err(OnCalendarFormatError::Unsupported("INTERVAL")),
+        };
+
+        let time_component = |values: &[u32], every: bool, repeat: Option<u32>, default: u32| {
+            if !values.is_empty() {
+                format_list(values)
+            } else if let Some(repeat) = repeat {
+                // The repetition divides the day, so its phase is that of DTSTART
+                format!("{:02}/{}", default % repeat, repeat)
+            } else if every {
+                "*".into()
+            } else {
+                format!("{:02}", default)
+            }
+        };
+
+        let hour = time_component(
+            &recur.by_hour,
+            recur.freq.is_sub_daily(),
+            hour_repeat,
+            dt_start.hour(),
+        );
+        let minute = time_component(
+            &recur.by_minute,
+            matches!(recur.freq, Frequency::Minutely | Frequency::Secondly),
+            minute_repeat,
+            dt_start.minute(),
+        );
+        let second = time_component(
+            &recur.by_second,
+            recur.freq == Frequency::Secondly,
+            None,
+            dt_start.second(),
+        );
+
+        let mut weekdays = vec![];
+        let mut nth = None;
+
+        for by_day in &recur.by_day {
+            match *by_day {
+                ByDay::All(weekday) => weekdays.push(weekday),
+                ByDay::Nth(weekday, n) => {
+                    if nth.is_some_and(|nth| nth != n) {
+                        return Err(OnCalendarFormatError::Unsupported(
+                            "BYDAY with different nth",
+                        ));
+                    }
+
+                    nth = Some(n);
+                    weekdays.push(weekday);
+                }
+            }
+        }
+
+        if nth.is_some() && recur.by_day.iter().any(|d| matches!(d, ByDay::All(_))) {
+            return Err(OnCalendarFormatError::Unsupported(
+                "BYDAY with and without nth",
+            ));
+        }
+
+        if recur.freq == Frequency::Weekly && weekdays.is_empty() {
+            weekdays.push(weekday_from_u32(dt_start.weekday().num_days_from_monday()));
+        }
+
+        let month = if !recur.by_month.is_empty() {
+            format_list(&recur.by_month)
+        } else if recur.freq == Frequency::Yearly {
+            match nth {
+                // Without BYMONTH the nth weekday is that of the year, the 5th may be in February
+                Some(1..=4) => "01".into(),
+                Some(-1) => "12".into(),
+                Some(_) => return Err(OnCalendarFormatError::Unsupported("BYDAY nth")),
+                // BYDAY and BYMONTHDAY expand the year into every month
+                None if weekdays.is_empty() && recur.by_month_day.is_empty() => {
+                    format!("{:02}", dt_start.month())
+                }
+                None => "*".into(),
+            }
+        } else {
+            "*".into()
+        };
+
+        let (separator, day) = match nth {
+            Some(_) if !recur.by_month_day.is_empty() => {
+                return Err(OnCalendarFormatError::Unsupported(
+                    "BYDAY nth with BYMONTHDAY",
+                ));
+            }
+            Some(n @ 1..=5) => (
+                '-',
+                format!("{:02}..{:02}", (n - 1) * 7 + 1, (n * 7).min(31)),
+            ),
+            Some(-1) => ('~', "07/1".into()),
+            Some(_) => return Err(OnCalendarFormatError::Unsupported("BYDAY nth")),
+            None => {
+                let positive: Vec<u32> = recur
+                    .by_month_day
+                    .iter()
+                    .filter(|&&d| d > 0)
+                    .map(|&d| d as u32)
+                    .collect();
+                let negative: Vec<u32> = recur
+                    .by_month_day
+                    .iter()
+                    .filter(|&&d| d < 0)
+                    .map(|&d| (-d) as u32)
+                    .collect();
+
+                match (positive.is_empty(), negative.is_empty()) {
+                    (false, true) => ('-', format_list(&positive)),
+                    (true, false) => ('~', format_list(&negative)),
+                    (false, false) => {
+                        return Err(OnCalendarFormatError::Unsupported(
+                            "positive and negative BYMONTHDAY",
+                        ))
+                    }
+                    (true, true) => {
+                        if matches!(recur.freq, Frequency::Monthly | Frequency::Yearly)
+                            && weekdays.is_empty()
+                        {
+                            ('-', format!("{:02}", dt_start.day()))
+                        } else {
+                            ('-', "*".into())
+                        }
+                    }
+                }
+            }
+        };
+
+        let mut out = String::new();
+
+        if !weekdays.is_empty() {
+            out.push_str(&format_weekdays(&weekdays));
+            out.push(' ');
+        }
+
+        out.push_str(&format!(
+            "*-{}{}{} {}:{}:{}",
+            month, separator, day, hour, minute, second
+        ));
+
+        match (self.dt_start.0.tz, self.dt_start.0.dt) {
+            (Some(tz), _) => out.push_str(&format!(" {}", tz.tzid())),
+            (None, Dt::DateTimeUtc(_)) => out.push_str(" UTC"),
+            _ => {}
+        }
+
+        Ok(out)
+    }
+}
+
+/// Format values as zero padded list, compressing runs into ranges: 1,2,3,5 => 01..03,05
+fn format_list(values: &[u32]) -> String {
+    let mut out = vec![];
+    let mut iter = values.iter().copied().peekable();
+
+    while let Some(start) = iter.next() {
+        let mut end = start;
+
+        while iter.peek() == Some(&(end + 1)) {
+            end = iter.next().unwrap();
+        }
+
+        match end - start {
+            0 => out.push(format!("{:02}", start)),
+            1 => out.push(format!("{:02},{:02}", start, end)),
+            _ => out.push(format!("{:02}..{:02}", start, end)),
+        }
+    }
+
+    out.join(",")
+}
+
+fn format_weekdays(weekdays: &[Weekday]) -> String {
+    const NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+    let mut weekdays = weekdays.to_vec();
+    weekdays.sort_unstable();
+    weekdays.dedup();
+
+    let mut out = vec![];
+    let mut iter = weekdays.iter().map(|&w| w as usize).peekable();
+
+    while let Some(start) = iter.next() {
+        let mut end = start;
+
+        while iter.peek() == Some(&(end + 1)) {
+            end = iter.next().unwrap();
+        }
+
+        match end - start {
+            0 => out.push(NAMES[start].to_string()),
+            1 => out.push(format!("{},{}", NAMES[start], NAMES[end])),
+            _ => out.push(format!("{}..{}", NAMES[start], NAMES[end])),
+        }
+    }
+
+    out.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "chrono-tz")]
+    fn from_on_calendar(spec: &str) -> String {
+        let start = NaiveDate::from_ymd_opt(2022, 1, 3).unwrap();
+        let rrule: RRule = RRule::from_on_calendar(spec, start).unwrap();
+
+        format!("DTSTART{}\nRRULE:{}", rrule.dt_start.0, rrule.recur)
+    }
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn parse() {
+        assert_eq!(
+            from_on_calendar("Mon..Fri *-*-* 09:00:00"),
+            "DTSTART:20220103T090000\nRRULE:FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR"
+        );
+        assert_eq!(
+            from_on_calendar("*-*-01 00:00"),
+            "DTSTART:20220103T000000\nRRULE:FREQ=DAILY;BYMONTHDAY=1"
+        );
+        assert_eq!(
+            from_on_calendar("*-*-* 00/2:30 Europe/Berlin"),
+            "DTSTART;TZID=Europe/Berlin:20220103T003000\n\
+            RRULE:FREQ=DAILY;BYHOUR=0,2,4,6,8,10,12,14,16,18,20,22"
+        );
+        assert_eq!(
+            from_on_calendar("hourly"),
+            "DTSTART:20220103T000000\nRRULE:FREQ=HOURLY"
+        );
+        assert_eq!(
+            from_on_calendar("Sat,Sun 2023-*~01 12:00"),
+            "DTSTART:20230101T120000\nRRULE:FREQ=DAILY;UNTIL=20231231T235959;BYDAY=SA,SU;BYMONTHDAY=-1"
+        );
+
+        assert!(matches!(
+            RRule::<Utc>::from_on_calendar(
+                "*-*-* 25:00",
+                NaiveDate::from_ymd_opt(2022, 1, 3).unwrap()
+            ),
+            Err(OnCalendarParseError::OutOfRange("hour", 25))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn format() {
+        let to_on_calendar = |s: &str| s.parse::<RRule>().unwrap().to_on_calendar();
+
+        assert_eq!(
+            to_on_calendar("DTSTART:20220103T090000\nRRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR")
+                .unwrap(),
+            "Mon..Fri *-*-* 09:00:00"
+        );
+        assert_eq!(
+            to_on_calendar("DTSTART;TZID=Europe/Berlin:20220103T090000\nRRULE:FREQ=MONTHLY")
+                .unwrap(),
+            "*-*-03 09:00:00 Europe/Berlin"
+        );
+        assert_eq!(
+            to_on_calendar("DTSTART:20220103T090000Z\nRRULE:FREQ=MONTHLY;BYDAY=-1FR").unwrap(),
+            "Fri *-*~07/1 09:00:00 UTC"
+        );
+        assert_eq!(
+            to_on_calendar("DTSTART:20220103T090000\nRRULE:FREQ=HOURLY;INTERVAL=2").unwrap(),
+            "*-*-* 01/2:00:00"
+        );
+        assert_eq!(
+            to_on_calendar("DTSTART:20220103T091500\nRRULE:FREQ=MINUTELY;INTERVAL=20").unwrap(),
+            "*-*-* *:15/20:00"
+        );
+        assert!(matches!(
+            to_on_calendar("DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYDAY=5MO"),
+            Err(OnCalendarFormatError::Unsupported("BYDAY nth"))
+        ));
+        assert!(matches!(
+            to_on_calendar("DTSTART:20220103T090000\nRRULE:FREQ=DAILY;INTERVAL=3"),
+            Err(OnCalendarFormatError::Unsupported("INTERVAL"))
+        ));
+    }
+
+    #[test]
+    fn round_trip() {
+        for rule in [
+            "DTSTART:20220103T090000\nRRULE:FREQ=MONTHLY;BYDAY=-1FR",
+            "DTSTART:20220103T090000\nRRULE:FREQ=MONTHLY;BYDAY=2TU",
+            "DTSTART:20220103T090000\nRRULE:FREQ=WEEKLY;BYDAY=MO,WE;BYHOUR=9,17",
+            "DTSTART:20220103T090000\nRRULE:FREQ=YEARLY",
+            "DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYDAY=-1FR",
+            "DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYDAY=2MO",
+            "DTSTART:20220103T090000\nRRULE:FREQ=YEARLY;BYMONTHDAY=1",
+            "DTSTART:20220103T090000\nRRULE:FREQ=MINUTELY;BYHOUR=9",
+            "DTSTART:20220103T090000\nRRULE:FREQ=SECONDLY;BYHOUR=9;BYMINUTE=0",
+        ] {
+            let rrule: RRule = rule.parse().unwrap();
+            let spec = rrule.to_on_calendar().unwrap();
+            let parsed =
+                RRule::from_on_calendar(&spec, NaiveDate::from_ymd_opt(2022, 1, 3).unwrap())
+                    .unwrap();
+
+            assert!(
+                rrule.equivalent(&parsed).is_equivalent(),
+                "{rule} -> {spec}"
+            );
+        }
+
+        for spec in ["*-*-* 09:*:00", "*-*-* 09:00:*", "*-*-* 09:*:*"] {
+            let rrule: RRule =
+                RRule::from_on_calendar(spec, NaiveDate::from_ymd_opt(2022, 1, 3).unwrap())
+                    .unwrap();
+
+            assert_eq!(rrule.to_on_calendar().unwrap(), spec);
+        }
+    }
+}
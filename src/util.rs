@@ -1,12 +1,11 @@
-use crate::error::IResult;
+use crate::error::{with_kind, IResult, ParseError, ParseErrorKind};
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use core::str::FromStr;
 use nom::bytes::complete::take_while1;
 use nom::character::complete::char;
-use nom::combinator::{map_parser, map_res, opt};
+use nom::combinator::{all_consuming, cut, map_parser, map_res};
 use nom::error::context;
-use nom::multi::many1;
-use nom::sequence::terminated;
-use std::fmt;
-use std::str::FromStr;
 
 pub(crate) fn display_list<I, D>(f: &mut fmt::Formatter<'_>, name: &str, list: I) -> fmt::Result
 where
@@ -31,29 +30,56 @@ where
     F: Fn(&str) -> IResult<&str, T> + Copy,
 {
     move |i| {
-        many1(terminated(
-            map_parser(take_while1(|c| c != del), f),
-            opt(char(del)),
-        ))(i)
+        let (mut i, first) = parse_item(i, f, del)?;
+        let mut items = vec![first];
+
+        // Every delimiter but a trailing one must be followed by a valid item
+        while let Ok((rem, _)) = char::<_, ParseError>(del)(i) {
+            if rem.is_empty() || rem.starts_with(['\r', '\n']) {
+                return Ok((rem, items));
+            }
+
+            let (rem, next) = cut(|i| parse_item(i, f, del))(rem)?;
+            items.push(next);
+            i = rem;
+        }
+
+        Ok((i, items))
     }
 }
 
+fn parse_item<F, T>(i: &str, f: F, del: char) -> IResult<&str, T>
+where
+    F: Fn(&str) -> IResult<&str, T>,
+{
+    map_parser(
+        take_while1(|c| c != del && !matches!(c, '\r' | '\n')),
+        all_consuming(f),
+    )(i)
+}
+
 pub(crate) fn parse_i32(i: &str) -> IResult<&str, i32> {
-    context(
-        "expected i32",
-        map_res(
-            take_while1(|c| matches!(c, '0'..='9' | '-' | '+')),
-            FromStr::from_str,
+    with_kind(
+        ParseErrorKind::InvalidNumber,
+        context(
+            "expected i32",
+            map_res(
+                take_while1(|c| matches!(c, '0'..='9' | '-' | '+')),
+                FromStr::from_str,
+            ),
         ),
     )(i)
 }
 
 pub(crate) fn parse_u32(i: &str) -> IResult<&str, u32> {
-    context(
-        "expected u32",
-        map_res(
-            take_while1(|c| matches!(c, '0'..='9' | '+')),
-            FromStr::from_str,
+    with_kind(
+        ParseErrorKind::InvalidNumber,
+        context(
+            "expected u32",
+            map_res(
+                take_while1(|c| matches!(c, '0'..='9' | '+')),
+                FromStr::from_str,
+            ),
         ),
     )(i)
 }
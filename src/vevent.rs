@@ -0,0 +1,536 @@
+use crate::dt_prop::{local_datetime_with_tz, RDate, RecurrenceId};
+use crate::error::{IResult, ParseError};
+use crate::interval::{EventEnd, Length};
+use crate::iter::{RRuleIterYield, RRuleSetIter};
+use crate::rrule_set::{RRuleSet, RRuleSetFromStrError};
+use crate::zone::{DefaultZone, Zone};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+use chrono::NaiveDateTime;
+use core::str::FromStr;
+use nom::combinator::all_consuming;
+use nom::Finish;
+
+/// VEVENT component of [RFC5545#3.6.1], limited to the properties describing when it occurs
+///
+/// Other properties and nested components such as VALARM are skipped.
+///
+/// [RFC5545#3.6.1]: https://datatracker.ietf.org/doc/html/rfc5545#section-3.6.1
+#[derive(Debug, Clone)]
+pub struct VEvent<Z: Zone = DefaultZone> {
+    pub uid: Option<String>,
+    pub summary: Option<String>,
+    /// DTSTART and the recurrence properties of the event
+    pub set: RRuleSet<Z>,
+    pub end: Option<EventEnd<Z>>,
+    /// Occurrence of the master event this event overrides
+    pub recurrence_id: Option<RecurrenceId<Z>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VEventParseError {
+    #[error("invalid {0}: {1}")]
+    InvalidProperty(String, Box<ParseError>),
+    #[error("invalid recurrence: {0}")]
+    InvalidRecurrence(#[from] RRuleSetFromStrError),
+    #[error("missing DTSTART")]
+    MissingDtStart,
+    #[error("duplicate {0}")]
+    DuplicateProperty(String),
+    #[error("missing END:{0}")]
+    Unterminated(String),
+    #[error("no VEVENT found")]
+    NoEvent,
+    #[error("overrides of '{0}' without master event")]
+    MissingMaster(String),
+    #[error("more than one master event '{0}'")]
+    DuplicateMaster(String),
+}
+
+/// Lines of the input with folded lines joined
+fn unfold(s: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+
+    for line in s.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continued), Some(last)) => last.push_str(continued),
+            _ if line.is_empty() => {}
+            _ => lines.push(line.into()),
+        }
+    }
+
+    lines
+}
+
+/// Name of a content line, e.g. `DTSTART` of `DTSTART;TZID=Europe/Berlin:20220103T090000`
+fn name(line: &str) -> &str {
+    line.split([';', ':']).next().unwrap_or_default()
+}
+
+/// Value of a content line, skipping parameters which may contain quoted colons
+fn value(line: &str) -> &str {
+    let mut quoted = false;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return &line[i + 1..],
+            _ => {}
+        }
+    }
+
+    ""
+}
+
+/// TEXT value of [RFC5545#3.3.11]
+///
+/// [RFC5545#3.3.11]: https://datatracker.ietf.org/doc/html/rfc5545#section-3.3.11
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(escaped) => unescaped.push(escaped),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+fn property<'a, T>(
+    line: &'a str,
+    parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> Result<T, VEventParseError> {
+    all_consuming(parser)(line)
+        .finish()
+        .map(|(_, value)| value)
+        .map_err(|e| VEventParseError::InvalidProperty(name(line).into(), Box::new(e.locate(line))))
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, line: &str) -> Result<(), VEventParseError> {
+    match slot.replace(value) {
+        Some(_) => Err(VEventParseError::DuplicateProperty(name(line).into())),
+        None => Ok(()),
+    }
+}
+
+impl<Z: Zone> VEvent<Z> {
+    /// Parse all VEVENTs of the input, e.g. of a VCALENDAR
+    pub fn parse_all(s: &str) -> Result<Vec<Self>, VEventParseError> {
+        let mut events = vec![];
+        let mut lines = unfold(s).into_iter();
+
+        while let Some(line) = lines.next() {
+            if line.eq_ignore_ascii_case("BEGIN:VEVENT") {
+                events.push(Self::from_lines(&mut lines)?);
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Parse the properties of an event, up to its END line
+    fn from_lines(lines: &mut impl Iterator<Item = String>) -> Result<Self, VEventParseError> {
+        let mut uid = None;
+        let mut summary = None;
+        let mut recurrence = vec![];
+        let mut end = None;
+        let mut recurrence_id = None;
+
+        loop {
+            let line = lines
+                .next()
+                .ok_or_else(|| VEventParseError::Unterminated("VEVENT".into()))?;
+
+            match name(&line).to_ascii_uppercase().as_str() {
+                "END" => break,
+                "BEGIN" => {
+                    // Skip nested components
+                    let end_line = format!("END:{}", value(&line));
+
+                    if !lines.any(|line| line.eq_ignore_ascii_case(&end_line)) {
+                        return Err(VEventParseError::Unterminated(value(&line).into()));
+                    }
+                }
+                "UID" => set_once(&mut uid, unescape(value(&line)), &line)?,
+                "SUMMARY" => set_once(&mut summary, unescape(value(&line)), &line)?,
+                "DTSTART" | "RRULE" | "EXRULE" | "RDATE" | "EXDATE" => recurrence.push(line),
+                "DTEND" | "DURATION" => {
+                    set_once(&mut end, property(&line, EventEnd::parse)?, &line)?
+                }
+                "RECURRENCE-ID" => set_once(
+                    &mut recurrence_id,
+                    property(&line, RecurrenceId::parse)?,
+                    &line,
+                )?,
+                _ => {}
+            }
+        }
+
+        if !recurrence.iter().any(|line| name(line) == "DTSTART") {
+            return Err(VEventParseError::MissingDtStart);
+        }
+
+        Ok(Self {
+            uid,
+            summary,
+            set: recurrence.join("\n").parse()?,
+            end,
+            recurrence_id,
+        })
+    }
+}
+
+impl<Z: Zone> FromStr for VEvent<Z> {
+    type Err = VEventParseError;
+
+    /// Parse the first VEVENT of the input
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_all(s)?
+            .into_iter()
+            .next()
+            .ok_or(VEventParseError::NoEvent)
+    }
+}
+
+/// A recurring event with the events overriding single occurrences of it
+#[derive(Debug, Clone)]
+pub struct Series<Z: Zone = DefaultZone> {
+    pub master: VEvent<Z>,
+    pub overrides: Vec<VEvent<Z>>,
+}
+
+/// Occurrence of a [`Series`]
+#[derive(Debug, Clone, Copy)]
+pub struct Occurrence<'a, Z: Zone = DefaultZone> {
+    /// Start of the occurrence as given by the master event
+    pub recurrence_id: RRuleIterYield<Z>,
+    pub start: RRuleIterYield<Z>,
+    pub end: Option<RRuleIterYield<Z>>,
+    /// Override of the occurrence, or the master event
+    pub event: &'a VEvent<Z>,
+}
+
+impl<Z: Zone> Series<Z> {
+    /// Group all VEVENTs of the input by UID, events without UID each form their own series
+    pub fn parse_all(s: &str) -> Result<Vec<Self>, VEventParseError> {
+        let mut series: Vec<Self> = vec![];
+        let mut overrides = vec![];
+
+        for event in VEvent::parse_all(s)? {
+            if event.recurrence_id.is_some() {
+                overrides.push(event);
+            } else if let Some(uid) = event.uid.as_ref().filter(|&uid| {
+                series
+                    .iter()
+                    .any(|series| series.master.uid.as_ref() == Some(uid))
+            }) {
+                return Err(VEventParseError::DuplicateMaster(uid.clone()));
+            } else {
+                series.push(Self {
+                    master: event,
+                    overrides: vec![],
+                });
+            }
+        }
+
+        for event in overrides {
+            let master = series
+                .iter_mut()
+                .find(|series| series.master.uid.is_some() && series.master.uid == event.uid);
+
+            match master {
+                Some(master) => master.overrides.push(event),
+                None => {
+                    return Err(VEventParseError::MissingMaster(
+                        event.uid.unwrap_or_default(),
+                    ))
+                }
+            }
+        }
+
+        Ok(series)
+    }
+
+    /// Iterator over the occurrences with their overrides applied
+    pub fn iter(&self) -> SeriesIter<'_, Z> {
+        SeriesIter::new(self)
+    }
+}
+
+/// Iterator over the occurrences of a [`Series`], see [`Series::iter`]
+///
+/// Occurrences are ordered by their recurrence id, an overridden occurrence may start earlier
+/// or later. The first occurrence is DTSTART of the master event, and overrides of instants
+/// which are not occurrences of the master event add occurrences.
+///
+/// An override with `RANGE=THISANDFUTURE` also applies to all later occurrences, until the
+/// next such override. Their start is moved by the same wall clock offset and they last as
+/// long as the override. RDATEs, EXDATEs and COUNT refer to the original occurrences.
+#[derive(Debug)]
+pub struct SeriesIter<'a, Z: Zone = DefaultZone> {
+    series: &'a Series<Z>,
+    tz: Option<Z>,
+    iter: RRuleSetIter<Z>,
+    overrides: BTreeMap<NaiveDateTime, &'a VEvent<Z>>,
+    // Sorted by recurrence id
+    ranges: Vec<(RRuleIterYield<Z>, &'a VEvent<Z>)>,
+}
+
+impl<'a, Z: Zone> SeriesIter<'a, Z> {
+    fn new(series: &'a Series<Z>) -> Self {
+        let master = &series.master;
+        let tz = master.set.dt_start.0.result_tz();
+
+        let mut set = master.set.clone();
+        set.rdates.push(RDate(master.set.dt_start.0));
+
+        let mut overrides = BTreeMap::new();
+        let mut ranges = vec![];
+
+        for event in &series.overrides {
+            if let Some(recurrence_id) = event.recurrence_id {
+                set.rdates.push(RDate(recurrence_id.dt));
+
+                let instant = RRuleIterYield::from_property(recurrence_id.dt, tz);
+                overrides.insert(instant.sort_key(), event);
+
+                if recurrence_id.this_and_future {
+                    ranges.push((instant, event));
+                }
+            }
+        }
+
+        ranges.sort_by_key(|(instant, _)| instant.sort_key());
+
+        Self {
+            series,
+            tz,
+            iter: set.iter(),
+            overrides,
+            ranges,
+        }
+    }
+
+    /// Length of the occurrences of an event, `None` if it has no end
+    fn length(event: &VEvent<Z>) -> Option<Length> {
+        event.end.map(|end| end.length(&event.set.dt_start))
+    }
+}
+
+/// Move an occurrence by the offset of the wall clock times of `from` and `to`
+fn shift<Z: Zone>(
+    occurrence: RRuleIterYield<Z>,
+    from: RRuleIterYield<Z>,
+    to: RRuleIterYield<Z>,
+) -> Option<RRuleIterYield<Z>> {
+    let local = occurrence
+        .local()
+        .checked_add_signed(to.local() - from.local())?;
+
+    Some(match occurrence {
+        RRuleIterYield::DateTimeLocal(_) => RRuleIterYield::DateTimeLocal(local),
+        RRuleIterYield::DateTimeTz(datetime) => {
+            RRuleIterYield::DateTimeTz(local_datetime_with_tz(local, datetime.timezone()))
+        }
+    })
+}
+
+impl<'a, Z: Zone> Iterator for SeriesIter<'a, Z> {
+    type Item = Occurrence<'a, Z>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let recurrence_id = self.iter.next()?;
+        let key = recurrence_id.sort_key();
+
+        // Latest THISANDFUTURE override at or before the occurrence
+        let range = self.ranges[..self
+            .ranges
+            .partition_point(|(instant, _)| instant.sort_key() <= key)]
+            .last()
+            .copied();
+
+        let master = &self.series.master;
+        let (inherited, inherited_length) = match range {
+            Some((_, event)) => (event, Self::length(event).or_else(|| Self::length(master))),
+            None => (master, Self::length(master)),
+        };
+
+        let occurrence = match self.overrides.get(&key) {
+            Some(&event) => {
+                let dt_start = event.set.dt_start;
+                let start = RRuleIterYield::from_property(dt_start.0, dt_start.0.result_tz());
+
+                // Without an end of its own the override lasts as long as the series
+                let length = Self::length(event).or(inherited_length);
+
+                Occurrence {
+                    recurrence_id,
+                    start,
+                    end: length.and_then(|length| length.end(start)),
+                    event,
+                }
+            }
+            None => {
+                let start = match range {
+                    Some((instant, event)) => shift(
+                        recurrence_id,
+                        instant,
+                        RRuleIterYield::from_property(event.set.dt_start.0, self.tz),
+                    )?,
+                    None => recurrence_id,
+                };
+
+                Occurrence {
+                    recurrence_id,
+                    start,
+                    end: inherited_length.and_then(|length| length.end(start)),
+                    event: inherited,
+                }
+            }
+        };
+
+        Some(occurrence)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "chrono-tz")]
+mod tests {
+    use super::*;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r\n\
+        BEGIN:VEVENT\r\n\
+        UID:standup\r\n\
+        SUMMARY:Standup\r\n\
+        DTSTART;TZID=Europe/Berlin:20220103T090000\r\n\
+        DURATION:PT15M\r\n\
+        RRULE:FREQ=DAILY;\r\n \
+        COUNT=3\r\n\
+        BEGIN:VALARM\r\n\
+        TRIGGER:-PT5M\r\n\
+        END:VALARM\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        UID:standup\r\n\
+        RECURRENCE-ID;TZID=Europe/Berlin:20220104T090000\r\n\
+        SUMMARY:Standup\\, moved\r\n\
+        DTSTART;TZID=Europe/Berlin:20220104T100000\r\n\
+        DTEND;TZID=Europe/Berlin:20220104T103000\r\n\
+        END:VEVENT\r\n\
+        END:VCALENDAR\r\n";
+
+    #[test]
+    fn overrides() {
+        let series: Vec<Series> = Series::parse_all(CALENDAR).unwrap();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].overrides.len(), 1);
+
+        let occurrences: Vec<_> = series[0]
+            .iter()
+            .map(|occurrence| {
+                format!(
+                    "{} {}-{} {}",
+                    occurrence.recurrence_id.local().format("%d"),
+                    occurrence.start.local().format("%H:%M"),
+                    occurrence.end.unwrap().local().format("%H:%M"),
+                    occurrence.event.summary.as_deref().unwrap_or_default()
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            occurrences,
+            [
+                "03 09:00-09:15 Standup",
+                "04 10:00-10:30 Standup, moved",
+                "05 09:00-09:15 Standup"
+            ]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let override_only = CALENDAR.split("END:VEVENT\r\n").nth(1).unwrap();
+
+        assert!(matches!(
+            Series::<DefaultZone>::parse_all(&format!("{override_only}END:VEVENT")),
+            Err(VEventParseError::MissingMaster(uid)) if uid == "standup"
+        ));
+        assert!(matches!(
+            "BEGIN:VEVENT\nSUMMARY:Lunch\nEND:VEVENT".parse::<VEvent>(),
+            Err(VEventParseError::MissingDtStart)
+        ));
+        assert!(matches!(
+            "BEGIN:VEVENT\nDTSTART;RANGE=THISANDFUTURE:20220103T120000\nEND:VEVENT"
+                .parse::<VEvent>(),
+            Err(VEventParseError::InvalidRecurrence(_))
+        ));
+        assert!(matches!(
+            "BEGIN:VEVENT\nDTSTART:20220103T120000".parse::<VEvent>(),
+            Err(VEventParseError::Unterminated(_))
+        ));
+    }
+
+    #[test]
+    fn this_and_future() {
+        let series: Vec<Series> = Series::parse_all(
+            "BEGIN:VEVENT\n\
+            UID:sync\n\
+            SUMMARY:Sync\n\
+            DTSTART;TZID=Europe/Berlin:20220103T090000\n\
+            DURATION:PT30M\n\
+            RRULE:FREQ=DAILY;COUNT=5\n\
+            EXDATE;TZID=Europe/Berlin:20220106T090000\n\
+            END:VEVENT\n\
+            BEGIN:VEVENT\n\
+            UID:sync\n\
+            SUMMARY:Longer sync\n\
+            RECURRENCE-ID;RANGE=THISANDFUTURE;TZID=Europe/Berlin:20220104T090000\n\
+            DTSTART;TZID=Europe/Berlin:20220104T100000\n\
+            DURATION:PT1H\n\
+            END:VEVENT\n\
+            BEGIN:VEVENT\n\
+            UID:sync\n\
+            SUMMARY:Afternoon sync\n\
+            RECURRENCE-ID;TZID=Europe/Berlin:20220105T090000\n\
+            DTSTART;TZID=Europe/Berlin:20220105T140000\n\
+            END:VEVENT",
+        )
+        .unwrap();
+
+        let occurrences: Vec<_> = series[0]
+            .iter()
+            .map(|occurrence| {
+                format!(
+                    "{} {}-{} {}",
+                    occurrence.start.local().format("%d"),
+                    occurrence.start.local().format("%H:%M"),
+                    occurrence.end.unwrap().local().format("%H:%M"),
+                    occurrence.event.summary.as_deref().unwrap_or_default()
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            occurrences,
+            [
+                "03 09:00-09:30 Sync",
+                "04 10:00-11:00 Longer sync",
+                "05 14:00-15:00 Afternoon sync",
+                "07 10:00-11:00 Longer sync"
+            ]
+        );
+    }
+}
@@ -1,11 +1,11 @@
-use crate::error::IResult;
+use crate::error::{expected, IResult};
+use core::fmt;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::combinator::map;
 use nom::error::context;
-use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Weekday {
     Monday = 0,
     Tuesday = 1,
@@ -22,19 +22,24 @@ impl PartialEq<chrono::Weekday> for Weekday {
     }
 }
 
+const WEEKDAYS: &[&str] = &["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
 impl Weekday {
     pub(crate) fn parse(i: &str) -> IResult<&str, Self> {
-        context(
-            "invalid weekday value",
-            alt((
-                map(tag("MO"), |_| Self::Monday),
-                map(tag("TU"), |_| Self::Tuesday),
-                map(tag("WE"), |_| Self::Wednesday),
-                map(tag("TH"), |_| Self::Thursday),
-                map(tag("FR"), |_| Self::Friday),
-                map(tag("SA"), |_| Self::Saturday),
-                map(tag("SU"), |_| Self::Sunday),
-            )),
+        expected(
+            WEEKDAYS,
+            context(
+                "invalid weekday value",
+                alt((
+                    map(tag("MO"), |_| Self::Monday),
+                    map(tag("TU"), |_| Self::Tuesday),
+                    map(tag("WE"), |_| Self::Wednesday),
+                    map(tag("TH"), |_| Self::Thursday),
+                    map(tag("FR"), |_| Self::Friday),
+                    map(tag("SA"), |_| Self::Saturday),
+                    map(tag("SU"), |_| Self::Sunday),
+                )),
+            ),
         )(i)
     }
 
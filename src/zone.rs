@@ -0,0 +1,157 @@
+use alloc::format;
+use alloc::string::String;
+use chrono::{FixedOffset, Offset, TimeZone, Utc};
+use core::fmt::Debug;
+use core::hash::Hash;
+
+/// Timezone of date properties and occurrences
+///
+/// Implemented for [`chrono_tz::Tz`] with the `chrono-tz` feature, and for [`Utc`] and
+/// [`FixedOffset`]. Other zone databases can be plugged in by implementing this trait.
+pub trait Zone: TimeZone<Offset: Copy> + Copy + Eq + Hash + Debug {
+    /// Zone of a TZID parameter, `None` if it is unknown
+    fn from_tzid(tzid: &str) -> Option<Self>;
+
+    /// TZID parameter of the zone
+    fn tzid(&self) -> String;
+
+    /// Zone of date-times in UTC
+    fn utc() -> Self;
+
+    fn is_utc(&self) -> bool;
+}
+
+/// Zone used by the types of this crate unless another one is given
+///
+/// This depends on the `chrono-tz` feature, code which must build either way names its zone or
+/// is generic over [`Zone`], as are the conversions and the other APIs of this crate.
+#[cfg(feature = "chrono-tz")]
+pub type DefaultZone = chrono_tz::Tz;
+
+/// Zone used by the types of this crate unless another one is given
+#[cfg(not(feature = "chrono-tz"))]
+pub type DefaultZone = Utc;
+
+#[cfg(feature = "chrono-tz")]
+impl Zone for chrono_tz::Tz {
+    fn from_tzid(tzid: &str) -> Option<Self> {
+        tzid.parse().ok()
+    }
+
+    fn tzid(&self) -> String {
+        self.name().into()
+    }
+
+    fn utc() -> Self {
+        Self::UTC
+    }
+
+    fn is_utc(&self) -> bool {
+        matches!(
+            self,
+            Self::UTC | Self::Etc__UTC | Self::Etc__Universal | Self::Etc__Zulu
+        )
+    }
+}
+
+impl Zone for Utc {
+    fn from_tzid(tzid: &str) -> Option<Self> {
+        matches!(tzid, "UTC" | "Etc/UTC").then_some(Utc)
+    }
+
+    fn tzid(&self) -> String {
+        "UTC".into()
+    }
+
+    fn utc() -> Self {
+        Utc
+    }
+
+    fn is_utc(&self) -> bool {
+        true
+    }
+}
+
+/// TZIDs are offsets like `+0100`, or `UTC`
+///
+/// Offsets are written without a colon, which would end the TZID parameter.
+impl Zone for FixedOffset {
+    fn from_tzid(tzid: &str) -> Option<Self> {
+        match Utc::from_tzid(tzid) {
+            Some(utc) => Some(utc.fix()),
+            None => tzid.parse().ok(),
+        }
+    }
+
+    fn tzid(&self) -> String {
+        let offset = self.local_minus_utc();
+        let sign = if offset < 0 { '-' } else { '+' };
+        let minutes = offset.unsigned_abs() / 60;
+
+        format!("{}{:02}{:02}", sign, minutes / 60, minutes % 60)
+    }
+
+    fn utc() -> Self {
+        Utc.fix()
+    }
+
+    fn is_utc(&self) -> bool {
+        self.local_minus_utc() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iter::RRuleIterYield;
+    use crate::rrule::RRule;
+    use crate::rrule_set::RRuleSet;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn fixed_offset() {
+        let set: RRuleSet<FixedOffset> =
+            "DTSTART;TZID=-0530:20220101T090000\nRRULE:FREQ=DAILY;COUNT=2\nRDATE;TZID=UTC:20220110T120000"
+                .parse()
+                .unwrap();
+
+        let occurrences: Vec<_> = set
+            .iter()
+            .map(|occurrence| match occurrence {
+                RRuleIterYield::DateTimeTz(datetime) => datetime.to_rfc3339(),
+                RRuleIterYield::DateTimeLocal(_) => unreachable!(),
+            })
+            .collect();
+
+        assert_eq!(
+            occurrences,
+            [
+                "2022-01-01T09:00:00-05:30",
+                "2022-01-02T09:00:00-05:30",
+                "2022-01-10T06:30:00-05:30"
+            ]
+        );
+        assert_eq!(
+            set.to_string(),
+            "DTSTART;TZID=-0530:20220101T090000\nRRULE:FREQ=DAILY;COUNT=2\nRDATE;TZID=+0000:20220110T120000"
+        );
+
+        assert!("DTSTART;TZID=Europe/Berlin:20220101T090000"
+            .parse::<RRuleSet<FixedOffset>>()
+            .is_err());
+    }
+
+    #[test]
+    fn generic_conversions() {
+        let start = NaiveDate::from_ymd_opt(2022, 1, 3).unwrap();
+        let rrule = RRule::<FixedOffset>::from_on_calendar("Mon *-*-* 09:00 +0100", start).unwrap();
+
+        assert_eq!(
+            format!("DTSTART{}\nRRULE:{}", rrule.dt_start.0, rrule.recur),
+            "DTSTART;TZID=+0100:20220103T090000\nRRULE:FREQ=DAILY;BYDAY=MO"
+        );
+        assert_eq!(rrule.to_on_calendar().unwrap(), "Mon *-*-* 09:00:00 +0100");
+    }
+}
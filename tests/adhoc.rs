@@ -4,7 +4,7 @@ use std::io::Write;
 
 #[test]
 fn adhoc() {
-    let (rem, rrule) = RRule::parse(
+    let (rem, rrule): (_, RRule) = RRule::parse(
         "DTSTART;TZID=America/New_York:20200101T000000\nRRULE:FREQ=YEARLY;BYMONTH=1,2,3,4,5,6;BYDAY=-2MO",
     )
     .unwrap();
@@ -0,0 +1,85 @@
+use std::process::{Command, Output};
+
+fn rruler(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rruler"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn expand() {
+    let output = rruler(&[
+        "--format",
+        "iso",
+        "DTSTART;TZID=Europe/Berlin:20220103T090000 RRULE:FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20220110T080000Z",
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2022-01-03T09:00:00+01:00\n2022-01-05T09:00:00+01:00\n2022-01-10T09:00:00+01:00\n"
+    );
+}
+
+#[test]
+fn between() {
+    let output = rruler(&[
+        "--between",
+        "2022-03-01",
+        "2022-03-31",
+        "--tz",
+        "UTC",
+        "DTSTART;TZID=Europe/Berlin:20220103T090000\nRRULE:FREQ=WEEKLY;BYDAY=FR;INTERVAL=2",
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "20220304T080000Z\n20220318T080000Z\n"
+    );
+}
+
+#[test]
+fn invalid() {
+    let output = rruler(&["DTSTART:20220103T090000 RRULE:FREQ=WEEKLY;BYDAY=XX"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("invalid weekday value"));
+
+    let output = rruler(&["DTSTART:20220103T090000 RRULE:COUNT=3"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("missing FREQ"));
+
+    let output = rruler(&["DTSTART:20220103T090000 RRULE:FREQ=DAILY;BYHOUR=25"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("invalid BYHOUR value 25"));
+
+    assert_eq!(rruler(&["--count", "x"]).status.code(), Some(2));
+}
+
+#[test]
+fn lint() {
+    let output = rruler(&["lint", "DTSTART:20220131T090000 RRULE:FREQ=MONTHLY"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "RRULE:FREQ=MONTHLY: warning[W002]: months shorter than 31 days are skipped\n"
+    );
+
+    let output = rruler(&[
+        "lint",
+        "DTSTART:20220103T090000 RRULE:FREQ=DAILY;INTERVAL=0",
+    ]);
+
+    assert_eq!(output.status.code(), Some(1));
+}